@@ -68,6 +68,22 @@
 //! getter and setter will inherit their visibility from the field, so if the field is declared `pub(super)`, the getters
 //! and setters will too.
 //!
+//! Fields of type `Option<bool>` are packed too, using two bits each: one for whether the value is `Some`, and one for
+//! the value itself. Their getters return `Option<bool>` and their setters take `Option<bool>`. Use
+//! `#[pack_bools(skip)]` to keep such a field unpacked.
+//!
+//! Fields of type `[bool; N]`, where `N` is an integer literal, are packed into `N` consecutive bits. For a field
+//! `channels: [bool; 16]`, the getter `get_channels(index: usize) -> bool` and setter
+//! `set_channels(index: usize, value: bool)` access a single element and panic if the index is out of range, while
+//! `get_channels_array()` and `set_channels_array(values: [bool; 16])` read and write the whole array. Setting
+//! `#[pack_bools(default = true)]` on such a field sets all of its elements by default.
+//!
+//! With `#[pack_bools(raw_bits [= name])]`, the whole bitflags can be read and written at once with `packed_bits()` and
+//! `set_packed_bits(bits)`, which have the visibility of the struct, for persistence or FFI. If a name is specified,
+//! the methods are named `name()` and `set_name(bits)` instead, which avoids clashing with the accessors of a field
+//! named `packed_bits`. `MyStruct::ALL_BITS` is then the mask of the bits assigned to packed fields, leaving out
+//! reserved and unused bits, while `MyStruct::KNOWN_BITS` also includes the reserved bits.
+//!
 //! By adding options to the `#[pack_bools(..)]` attribute, you can configure options for the entire struct, using
 //! *global options*. Additionally, you can add `#[pack_bools(..)]` to `boolean` fields to configure options for just that
 //! field, using *local options*.
@@ -93,12 +109,91 @@
 //!     * Default values are `#[pack_bools(get = self get_%, set = self set_%)]`.
 //! * `#[pack_bools(no_getters)]` will not generate getters (aliased as `no_get`/`no_getter`)
 //! * Similarly `#[pack_bools(no_set/no_setter/no_setters)]` will not generate setters.
+//! * `#[pack_bools(with [= [vis] template])]` generates builder methods taking `self` by value, such as
+//!   `fn with_verbose(self, value: bool) -> Self`, for chaining like `Config { .. }.with_verbose(true).with_colors(false)`,
+//!   and makes setters return `&mut Self` (`&Self` with `storage = atomic` or `cell`) so that they can be chained too.
+//!   The template works like the one of getters and defaults to `self with_%`, such as `with = pub and_%`. The builder
+//!   methods are `const fn` for bools, `Option<bool>` and integers filling their type with the default storage, so that
+//!   constants can be built with them. Only available on structs.
 //! * `#[pack_bools(type = u16)]` will use `u16` as the data type for the bit flags. Available options are `u8`/`u16`/`u32`/
 //!   `u64`/`u128`/`auto`, where `auto` (the default option) automatically use the smallest of those types that can fit all
-//!   the bools in the struct.
+//!   the bools (and other packed fields) in the struct. If they don't fit in a `u128`, `auto` uses an array of `u64` words.
+//!   An array can also be requested explicitly, such as `#[pack_bools(type = [u64; 5])]`. In an array, fields other than
+//!   `[bool; N]` never straddle two words: such a field is moved to the start of the next word instead.
 //! * `#[pack_bools(field = <name>)]` will set the name of the field containing the bitflags, by default `packed_bools`.
 //! * `#[pack_bools(inline)]` will use the inline pattern for the bitflag field, i.e. create fields of the pattern
 //!   `packed_bools: u8`. This is the default option. Compare to `newtype` below.
+//! * `#[pack_bools(position = <index>)]` places the field containing the bitflags at the given index among the fields
+//!   that are left after packing. By default, it is placed last.
+//! * `#[pack_bools(strict_bits)]` requires every packed field to be pinned to a bit with `#[pack_bools(bit = <n>)]` (see
+//!   below), so that adding or reordering fields can't silently change the layout of the bitflags.
+//! * `#[pack_bools(reserve = <n>)]` leaves `n` unused bits after the packed fields. They count towards the size of the
+//!   bitflags and are never touched by the setters, so flags can be added later by lowering `n` without changing the
+//!   storage type or the bits of the existing fields.
+//! * `#[pack_bools(order = <order>)]` controls how fields are mapped to bits. `lsb0` (the default) numbers bits from the
+//!   least significant bit and `msb0` from the most significant bit (of each word, if the bitflags are stored in an
+//!   array), which applies to `bit = <n>` too. Multi-bit fields keep their significance either way. `declaration` (the
+//!   default) assigns bits to fields in declaration order and `alphabetical` in alphabetical order of their names. Both
+//!   kinds can be combined, as in `#[pack_bools(order = msb0, order = alphabetical)]`. The resulting mapping is stated
+//!   in the docs of every accessor, and structs get an associated constant per field holding its first bit, such as
+//!   `MyStruct::VERBOSE_BIT` (or `MyTuple::FIELD_0_BIT` for tuple structs).
+//! * `#[pack_bools(storage = atomic)]` stores the bitflags in an `AtomicU8`..`AtomicU64` (or an array of `AtomicU64`, as
+//!   `u128` isn't available) so that they can be shared between threads, for example behind an `Arc`. Setters then take
+//!   `&self`, and every field also gets a `swap_<field>` accessor returning its previous value, plus `fetch_set_<field>`
+//!   for bools, which sets it to `true`. When the bitflags fit in a single word, a `MyStructSnapshot` copy of them is
+//!   generated, with `load_packed_bools`, `update_packed_bools` and `compare_exchange_packed_bools` methods to read or
+//!   change several fields in one atomic operation. The default is `storage = plain`.
+//! * `#[pack_bools(storage = cell)]` stores the bitflags in a `Cell`, for state shared on a single thread (such as in an
+//!   `Rc`). As with `storage = atomic`, setters take `&self` and `swap_<field>`/`fetch_set_<field>` accessors are
+//!   generated. The newtype only derives `Clone` and `Debug` in this case, as `Cell` isn't `Copy`.
+//! * `#[pack_bools(storage = volatile)]` reads and writes the bitflags only with `read_volatile`/`write_volatile`, for
+//!   modelling memory-mapped registers with a `#[repr(transparent)]` struct or newtype. Each setter is a single
+//!   read-modify-write of the word, and when the bitflags fit in a single word, a `MyStructSnapshot` copy of them is
+//!   generated along with `read_packed_bools`, `write_packed_bools` and `modify_packed_bools` methods, the latter taking a
+//!   closure such as `|r| { r.set_enable(true); r.set_irq(false); }` to update several fields in one read-modify-write.
+//! * `#[pack_bools(ordering = <ordering>)]` sets the `Ordering` of the atomic operations with `storage = atomic`, one of
+//!   `Relaxed`, `Release`, `Acquire`, `AcqRel` or `SeqCst` (the default). Loads use `Relaxed` for `Release` and
+//!   `Acquire` for `AcqRel`.
+//! * `#[pack_bools(flags [= name])]` generates an enum with a variant per packed bool, named after the field in
+//!   `UpperCamelCase` (`Field0` for tuple fields), such as `enum ConfigFlag { Verbose, UseColors }` for a struct
+//!   `Config`. If a name is specified, the enum is defined with that name, otherwise `Flag` is suffixed to the name of
+//!   the struct. The struct then gets `get(flag)`, `set(flag, value)` and `toggle(flag)` methods and implements
+//!   `Index<ConfigFlag>`, and `ConfigFlag::ALL` lists all flags, for handling the bools generically. Only available on
+//!   structs, and fields other than bools don't get a flag. Fields whose variants would clash or be invalid, such as
+//!   `a_b` and `a__b` or `self_`, are rejected and can be renamed with `#[pack_bools(name = ..)]`.
+//! * `#[pack_bools(debug [= target])]` replaces the `Debug` output of the raw bitflags with the packed fields listed by
+//!   name, in declaration order. `debug` (or `debug = newtype`) implements `Debug` for the newtype, so that a derived
+//!   `Debug` on the struct shows `packed_bools: ConfigPackedBools { verbose: true, .. }`. `debug = struct` implements
+//!   `Debug` for the whole struct instead, with the packed fields in place of the bitflags, and removes `Debug` from its
+//!   `#[derive(..)]` attributes. Field values are formatted like their getters return them.
+//! * `#[pack_bools(serde [= format])]` makes a derived `Serialize`/`Deserialize` on the struct keep the packed fields, and
+//!   requires the `serde` feature of this crate. `serde` (or `serde = fields`) serializes every packed field under the
+//!   name of its original field, flattened into the struct, as if it had never been packed. With `newtype`, the newtype
+//!   implements `Serialize` and `Deserialize` as a struct of the packed fields, while inline bitflags are handled by
+//!   associated functions of the struct. Missing fields keep their default value (as set with `default = true`), unknown
+//!   fields are ignored, and integers that don't fit in their bits are rejected. `[bool; N]` fields are serialized as
+//!   sequences of bools, whatever their length. As the packed fields are flattened, tuple structs deriving these traits
+//!   need `serde = compact`, which serializes the raw bitflags instead, as an integer (or an array of integers) under the
+//!   name of the field containing them.
+//! * `#[pack_bools(unpacked [= name])]` generates a copy of the struct with its original fields, none of them packed,
+//!   along with `From` conversions in both directions. Struct literals, patterns and destructuring can then use the
+//!   original shape at API boundaries, such as `Config::from(ConfigUnpacked { verbose: true, .. })` or
+//!   `let ConfigUnpacked { verbose, .. } = config.into();`, while the stored struct stays packed. If the struct has enum
+//!   fields, whose bits may not convert back to a variant, it converts to the unpacked struct with `TryFrom` instead,
+//!   giving the struct back as the error. If a name is specified, the struct is defined with that name, otherwise
+//!   `Unpacked` is suffixed to the name of the struct. It gets the attributes of the struct other than `#[repr]`, such as
+//!   its derives and its docs (which clap uses for the help of `Parser`). Only available on structs.
+//! * `#[pack_bools(derive_unpacked(Parser, Deserialize, ..))]` generates the unpacked struct (see `unpacked`) with the
+//!   given derives on top of the ones of the struct, for derives that need the original fields such as clap's `Parser`.
+//!   The unpacked struct keeps the attributes of the fields, so `#[arg(long)]` and the like keep working there, and the
+//!   struct converts from it with `Config::from(ConfigUnpacked::parse())`. The helper attributes of these derives are
+//!   removed from the struct and its remaining fields, unless the struct itself has a derive using them. These are known
+//!   for the derives of clap (`arg`, `command`, ..), serde (`serde`) and schemars (`schemars`), and the helpers of other
+//!   derives can be listed with `#[pack_bools(unpacked_helpers(name, ..))]`. Other attributes are kept.
+//! * `#[pack_bools(new [= name])]` generates a constructor taking every field of the struct in declaration order, such as
+//!   `Config::new(output_name, verbose, use_colors, original_file, legacy_mode)`, since the packed fields prevent building
+//!   the struct with a literal. Reserved fields aren't taken and get their default value. If a name is specified, the
+//!   constructor gets that name instead of `new`. It has the visibility of the struct. Only available on structs.
 //! * `#[pack_bools(newtype [= name])]` will make a new single-valued tuple struct for holding the bitflags, similar to
 //!   `struct MyStructPackedBools(u8);`. If a name is specified, the newtype struct will be defined with that name,
 //!   otherwise `PackedBools` will be suffixed to the name of the struct. The example at the top of this document,
@@ -133,9 +228,25 @@
 //! }
 //! ```
 //!
+//!   Unless `storage` is `atomic` or `cell`, the newtype can also be used as a set of flags, like the types generated by
+//!   the `bitflags` crate. It gets a constant per packed bool (such as `ConfigPackedBools::VERBOSE`, which must not be
+//!   named `ALL_BITS` or `KNOWN_BITS`), `empty()`, `all()`, `contains`, `intersects`, `insert`, `remove`, `toggle`,
+//!   `is_empty`, `is_all` and `iter()` methods, and the `|`, `&`, `^`, `-` and `!` operators along with their assigning
+//!   variants. Packed fields other than bools are left out of `all()`, and `!` only inverts the bools. For persistence and
+//!   FFI, `bits()` returns the raw bits, while `from_bits(bits)` converts them back (returning `None` if a bit outside of
+//!   `ConfigPackedBools::KNOWN_BITS` is set), and `from_bits_truncate` and `from_bits_retain` respectively clear and keep
+//!   such bits. `KNOWN_BITS` includes the reserved bits, so that bits used by a newer layout are preserved.
+//!
+//!   Besides `Copy`, `Clone` and `Debug`, the newtype derives the `PartialEq`, `Eq`, `PartialOrd`, `Ord` and `Hash`
+//!   traits derived by the struct, so that the derives of the struct still compile (as far as the storage supports them:
+//!   atomics don't, and `Cell` isn't `Hash`). The derives of the newtype can be set explicitly instead with
+//!   `#[pack_bools(newtype_derive(Clone, PartialEq, ..))]`.
+//!
 //! ### Local options
 //!
-//! You may add the `#[pack_bools(..)]` attribute on fields of type `bool` to configure the output of that specific field.
+//! You may add the `#[pack_bools(..)]` attribute on fields of type `bool`, `Option<bool>` or `[bool; N]` to configure
+//! the output of that specific field, or on unsigned integer and enum fields together with `bits` (see below) to pack
+//! them too.
 //! Available options are:
 //!
 //! * `#[pack_bools(skip)]` excludes that field from being packed with the other bools.
@@ -147,22 +258,124 @@
 //!     * For setters, use `#[pack_bools(set/setter = [vis] [name])]`.
 //! * `#[pack_bools(no_getter)]` skips generating a getter for that field. Aliased as `no_get`. For setters, use
 //!   `#[pack_bools(no_set/no_setter)]`.
+//! * `#[pack_bools(with = [vis] [name])]` and `#[pack_bools(no_with)]` similarly change or skip the builder method of
+//!   that field (see the global `with` option). A field with `with = ..` gets one even without the global option, in
+//!   which case the setters still return `()`.
 //! * `#[pack_bools(default = <true/false>)]` sets the default value for the field. If set to `true`, the `newtype` pattern
 //!   must be used, but then a `impl Default` will be generated for that newtype with this field set to `true`. If all other
 //!   fields of the struct has appropriate default values, this will allow you to use `#[derive(Default)]` on the struct,
 //!   while having some boolean values set to `true`. Defaults to `false`.
+//!     * On `Option<bool>` fields, `#[pack_bools(default = <none/true/false>)]` is available instead, and setting it to
+//!       anything but `none` (the default) requires the `newtype` pattern.
+//! * `#[pack_bools(name = <name>)]` sets the name substituted for `%` in the global getter and setter templates, instead
+//!   of the name of the field. This is mostly useful for tuple structs, see below.
+//! * `#[pack_bools(bits = <n>)]` packs an unsigned integer field (`u8`/`u16`/`u32`/`u64`/`u128`) into `n` bits next to the
+//!   bools. The getter returns the type of the field, and the setter takes it. Setting a value that doesn't fit in `n`
+//!   bits panics in debug builds, while only the lowest `n` bits are stored in release builds. For example,
+//!   `#[pack_bools(bits = 3)] level: u8` stores values `0..=7` and generates `fn get_level(&self) -> u8` and
+//!   `fn set_level(&mut self, value: u8)`.
+//! * `#[pack_bools(enum, bits = <n>)]` packs a fieldless enum into `n` bits next to the bools. The value is converted
+//!   through the smallest unsigned integer type fitting `n` bits (`u8` for up to 8 bits, `u16` for up to 16 bits and so
+//!   on), using `From<Enum> for u8` when setting it and `TryFrom<u8> for Enum` when getting it. Since not every bit
+//!   pattern has to be a valid variant, the getter returns the result of `TryFrom`. These conversions can be implemented
+//!   manually, or generated by adding `#[pack_bools]` to the enum, see below.
+//! * `#[pack_bools(bit = <n>)]` pins the field to start at bit `n` of the bitflags. By default, fields are laid out in
+//!   declaration order, so reordering them changes the layout; pinning fields keeps it stable, for example when the
+//!   bitflags are persisted. Fields that aren't pinned are placed in the free bits around the pinned ones, still in
+//!   declaration order. Pinning two fields to overlapping bits, or to bits beyond the width given by `type`, is an error.
+//! * `#[pack_bools(reserved)]` turns the field into a placeholder: its bits are reserved (and can be pinned with `bit`)
+//!   but no accessors are generated for it, and the field is removed like any other packed field. For example,
+//!   `#[pack_bools(reserved)] _future: [bool; 4]` reserves four bits for later use.
+//!
+//! ### Tuple structs
+//!
+//! `#[pack_bools]` can also be used on tuple structs, which keep their tuple form. The field containing the bitflags is
+//! placed last unless `#[pack_bools(position = <index>)]` is used, and the `field` option has no effect. Since tuple fields
+//! have no names, the index of the field in the original struct is substituted for `%` in the accessor templates, unless
+//! the field has a `#[pack_bools(name = ..)]` attribute (unit structs have nothing to pack and are left unchanged):
+//!
+//! ```rust
+//! use pack_bools::pack_bools;
+//!
+//! #[pack_bools(position = 0)]
+//! struct Flags(u32, bool, #[pack_bools(name = verbose)] bool);
+//!
+//! fn example(flags: &mut Flags) {
+//!     flags.set_1(true);
+//!     flags.set_verbose(flags.get_1());
+//!     flags.1 += 1;
+//! }
+//! ```
+//!
+//! ### Enums
+//!
+//! `#[pack_bools]` can also be used on enums. Each variant with fields to pack gets its own field containing the
+//! bitflags, while the global and local options apply to the fields of every variant. Since a field might not be
+//! present in the current variant, getters return `Option<bool>` and setters return whether the current variant has
+//! the field. Fields with the same name in different variants share their getter and setter:
+//!
+//! ```rust
+//! use pack_bools::pack_bools;
+//!
+//! #[pack_bools]
+//! enum State {
+//!     Idle,
+//!     Connecting { verbose: bool, retries: u32 },
+//!     Connected { verbose: bool, secure: bool },
+//! }
+//!
+//! fn example(state: &mut State) {
+//!     if state.get_verbose() == Some(true) {
+//!         let was_connected = state.set_secure(true);
+//!     }
+//! }
+//! ```
+//!
+//! All variants share the same type for their bitflags, large enough to fit the variant with the most bools. The local
+//! `default = true` option is not available on enum variants.
+//!
+//! Using `#[pack_bools]` on a fieldless enum instead generates the conversions needed to pack it into another struct
+//! with `#[pack_bools(enum, bits = ..)]`: `From<Enum>` for every unsigned integer type its discriminants fit in, and
+//! `TryFrom` from these types back to the enum, returning the invalid value as the error. These are its `#[repr]` type
+//! (or `u8` if it has none) and the wider types, along with the narrower types if all discriminants are integer literals
+//! (or follow one). Since there are several `TryFrom` impls, integer literals converted to the enum need a suffix, as in
+//! `LogLevel::try_from(3u8)`. No options can be given to `#[pack_bools]` on a fieldless enum.
+//!
+//! ```rust
+//! use pack_bools::pack_bools;
+//!
+//! #[pack_bools]
+//! #[derive(Debug, PartialEq)]
+//! enum LogLevel {
+//!     Error = 1,
+//!     Warn,
+//!     Info,
+//! }
+//!
+//! #[pack_bools]
+//! struct Config {
+//!     verbose: bool,
+//!     #[pack_bools(enum, bits = 2)]
+//!     level: LogLevel,
+//! }
+//!
+//! fn example(config: &mut Config) {
+//!     config.set_level(LogLevel::Warn);
+//!     assert_eq!(config.get_level(), Ok(LogLevel::Warn));
+//! }
+//! ```
 use proc_macro::TokenStream;
 
-use syn::{ItemStruct, parse_macro_input};
+use syn::{Item, parse_macro_input};
 
 use self::pack_bools::config::GlobalConfig;
 
 mod pack_bools;
 
 #[proc_macro_attribute]
-/// Packs the bools in this struct into one numeric field
+/// Packs the bools in this struct (or in each variant of this enum) into one numeric field
 pub fn pack_bools(config: TokenStream, tokens: TokenStream) -> TokenStream {
-    let item_struct = parse_macro_input!(tokens as ItemStruct);
+    let item = parse_macro_input!(tokens as Item);
     let config = parse_macro_input!(config as GlobalConfig);
-    pack_bools::pack_bools(config, item_struct).into()
+    pack_bools::pack_bools(config, item).into()
 }
@@ -1,8 +1,8 @@
 use std::fmt::Display;
 
 use proc_macro2::{Ident, Span, TokenStream};
-use quote::{format_ident, TokenStreamExt, ToTokens};
-use syn::{Error, parse_quote};
+use quote::{TokenStreamExt, ToTokens};
+use syn::{Error, parse_quote, Path};
 
 use crate::pack_bools::config::Visibility;
 
@@ -15,9 +15,43 @@ pub struct Config {
     pub skip_getter: bool,
     pub setter: VisibilityTemplate,
     pub skip_setter: bool,
+    /// The template of the `with_*` builder methods
+    pub with: VisibilityTemplate,
+    /// Whether `with_*` methods aren't generated unless set on the field, in which case setters return `()`
+    pub skip_with: bool,
     pub packed_type: PackingStrategy,
     pub field_name: FieldName,
     pub gen_type: GenType,
+    pub position: Option<usize>,
+    /// Whether every packed field has to be pinned with `bit`
+    pub strict_bits: bool,
+    /// The number of bits to leave unused after the packed fields
+    pub reserve: usize,
+    pub bit_order: BitOrder,
+    pub field_order: FieldOrder,
+    pub storage_mode: StorageMode,
+    /// The `Ordering` of atomic operations, if given
+    pub ordering: Option<Ident>,
+    /// Whether to generate an enum with a variant per packed bool, along with its name if given
+    pub flag_enum: Option<Option<Ident>>,
+    /// Which type gets a `Debug` impl listing the packed fields, if any
+    pub debug: Option<DebugImpl>,
+    /// The derives of the newtype, if set explicitly
+    pub newtype_derives: Option<Vec<Path>>,
+    /// How the packed fields are serialized with `serde`, if at all
+    pub serde: Option<SerdeFormat>,
+    /// Whether to generate a struct with the original fields unpacked, along with its name if given
+    pub unpacked: Option<Option<Ident>>,
+    /// The derives of the unpacked struct besides the ones of the struct, if given
+    pub unpacked_derives: Option<Vec<Path>>,
+    /// The helper attributes of the derives of the unpacked struct, besides the well-known ones
+    pub unpacked_helpers: Vec<Ident>,
+    /// Whether to generate a constructor taking every field, along with its name if given
+    pub constructor: Option<Option<Ident>>,
+    /// Whether to generate methods reading and writing the raw bitflags, along with their name if given
+    pub raw_bits: Option<Option<Ident>>,
+    /// The span of the first option, if any option is given
+    pub options_span: Option<Span>,
 }
 
 #[repr(transparent)]
@@ -40,29 +74,167 @@ impl GenType {
     }
 }
 
+/// How the bits of the bitflags are numbered
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BitOrder {
+    /// Bit 0 is the least significant bit
+    Lsb0,
+    /// Bit 0 is the most significant bit (of each word, if the bitflags are stored in an array)
+    Msb0,
+}
+
+/// The order in which fields that aren't pinned to a bit are assigned bits
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FieldOrder {
+    Declaration,
+    /// Sorted by the name substituted for `%` in accessor templates
+    Alphabetical,
+}
+
+/// How the words of the bitflags are stored and accessed
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StorageMode {
+    /// Plain unsigned integers, modified through `&mut self`
+    Plain,
+    /// Atomic integers, modified through `&self`
+    Atomic,
+    /// Plain unsigned integers in a `Cell`, modified through `&self` on a single thread
+    Cell,
+    /// Plain unsigned integers, only read and written with volatile operations
+    Volatile,
+}
+
+impl StorageMode {
+    /// Whether the bitflags can be modified through a shared reference
+    pub fn is_shared(self) -> bool {
+        matches!(self, StorageMode::Atomic | StorageMode::Cell)
+    }
+
+    /// The widest type usable as a word
+    fn largest_word(self) -> PackedType {
+        match self {
+            StorageMode::Plain | StorageMode::Cell | StorageMode::Volatile => PackedType::U128,
+            // There is no stable `AtomicU128`
+            StorageMode::Atomic => PackedType::U64,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            StorageMode::Plain => "plain",
+            StorageMode::Atomic => "atomic",
+            StorageMode::Cell => "cell",
+            StorageMode::Volatile => "volatile",
+        }
+    }
+}
+
+/// The type getting a `Debug` impl listing the packed fields by name
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DebugImpl {
+    NewType,
+    /// The whole struct, replacing its derived `Debug` impl
+    Struct,
+}
+
+/// How the `Serialize` and `Deserialize` impls generated for the bitflags represent them
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SerdeFormat {
+    /// The packed fields, each under the name of its original field
+    Fields,
+    /// The raw bitflags
+    Compact,
+}
+
 #[derive(Debug)]
 pub enum PackingStrategy {
     Auto,
     FixedType(PackedType),
+    /// An array of the given number of words of the given type
+    FixedArray(PackedType, usize),
 }
 
 impl PackingStrategy {
-    pub fn to_packed_type(&self, length: usize, span: Span) -> syn::Result<PackedType> {
+    /// The number of bits of the storage, if it doesn't depend on the packed fields
+    pub fn fixed_bit_width(&self) -> Option<usize> {
         match self {
-            PackingStrategy::Auto => PackedType::smallest_larger_than(length).ok_or_else(|| {
-                Error::new(
+            PackingStrategy::Auto => None,
+            PackingStrategy::FixedType(t) => Some(t.bit_width() as usize),
+            PackingStrategy::FixedArray(t, words) => Some(t.bit_width() as usize * words),
+        }
+    }
+
+    /// Chooses the storage for `length` bits. If the bits are stored in an array of words, `align` is
+    /// called with the word type to lay out the fields within the words, and returns the number of bits
+    /// needed afterwards.
+    pub fn to_storage(
+        &self,
+        mode: StorageMode,
+        length: usize,
+        span: Span,
+        mut align: impl FnMut(PackedType) -> syn::Result<usize>,
+    ) -> syn::Result<Storage> {
+        let largest = mode.largest_word().bit_width();
+        if let PackingStrategy::FixedType(t) | PackingStrategy::FixedArray(t, _) = self {
+            if t.bit_width() > largest {
+                return Err(Error::new(
                     span,
-                    "This struct contains more than 128 bools and doesn't fit in a u128",
-                )
-            }),
+                    format!(
+                        "storage = {} supports words of up to {largest} bits, use an array such as [u{largest}; 2] instead",
+                        mode.name()
+                    ),
+                ));
+            }
+        }
+        match self {
+            PackingStrategy::Auto => {
+                if let Some(t) =
+                    PackedType::smallest_larger_than(length).filter(|t| t.bit_width() <= largest)
+                {
+                    Ok(Storage::Int(t))
+                } else {
+                    let word = PackedType::U64;
+                    let length = align(word)?;
+                    Ok(Storage::Words(
+                        word,
+                        length.div_ceil(word.bit_width() as usize),
+                    ))
+                }
+            }
             PackingStrategy::FixedType(t) => {
                 let t = *t;
                 if (t.bit_width() as usize) < length {
                     Err(Error::new(span, "This struct contains more bools than would fit in your specified bit width"))
                 } else {
-                    Ok(t)
+                    Ok(Storage::Int(t))
                 }
             }
+            PackingStrategy::FixedArray(t, words) => {
+                let t = *t;
+                if align(t)? > t.bit_width() as usize * words {
+                    Err(Error::new(span, "This struct contains more bools than would fit in your specified bit width"))
+                } else {
+                    Ok(Storage::Words(t, *words))
+                }
+            }
+        }
+    }
+}
+
+/// The type of the bitflags
+#[derive(Debug, Copy, Clone)]
+pub enum Storage {
+    /// A single unsigned integer
+    Int(PackedType),
+    /// An array of the given number of unsigned integers, with bit `n` stored in word `n / width`
+    Words(PackedType, usize),
+}
+
+impl Storage {
+    /// The type of a single word of the bitflags
+    pub fn word(self) -> PackedType {
+        match self {
+            Storage::Int(t) | Storage::Words(t, _) => t,
         }
     }
 }
@@ -101,6 +273,14 @@ impl PackedType {
             .find(|variant| variant.bit_width() as usize >= length)
             .copied()
     }
+
+    /// This type followed by the wider types
+    pub fn and_wider(self) -> impl Iterator<Item = Self> {
+        PACKED_TYPE_VARIANTS
+            .iter()
+            .copied()
+            .filter(move |variant| variant.bit_width() >= self.bit_width())
+    }
 }
 
 impl ToTokens for PackedType {
@@ -134,6 +314,16 @@ impl Template {
         let Self { before, after } = self;
         format!("{before}{item}{after}")
     }
+
+    pub fn format_ident<T: Display>(&self, item: T, span: Span) -> syn::Result<Ident> {
+        let name = self.format(item);
+        syn::parse_str(&name).map_err(|_| {
+            Error::new(
+                span,
+                format!("`{name}` is not a valid identifier: use #[pack_bools(name = ..)] on the field or change the template"),
+            )
+        })
+    }
 }
 
 impl Default for Config {
@@ -149,9 +339,31 @@ impl Default for Config {
                 visibility: Visibility::Inherit,
             },
             skip_setter: false,
+            with: VisibilityTemplate {
+                template: Template::from_str("with_", ""),
+                visibility: Visibility::Inherit,
+            },
+            skip_with: true,
             packed_type: PackingStrategy::Auto,
             field_name: FieldName("packed_bools".to_string()),
             gen_type: GenType::Inline,
+            position: None,
+            strict_bits: false,
+            reserve: 0,
+            bit_order: BitOrder::Lsb0,
+            field_order: FieldOrder::Declaration,
+            storage_mode: StorageMode::Plain,
+            ordering: None,
+            flag_enum: None,
+            debug: None,
+            newtype_derives: None,
+            serde: None,
+            unpacked: None,
+            unpacked_derives: None,
+            unpacked_helpers: vec![],
+            constructor: None,
+            raw_bits: None,
+            options_span: None,
         }
     }
 }
@@ -169,11 +381,12 @@ pub struct UpdateVisibilityTemplate {
 }
 
 impl VisibilityTemplate {
-    pub fn get_formatted_parts(&self, field: impl Display) -> (&Visibility, Ident) {
-        (
-            &self.visibility,
-            format_ident!("{}", self.template.format(field)),
-        )
+    pub fn get_formatted_parts(
+        &self,
+        field: impl Display,
+        span: Span,
+    ) -> syn::Result<(&Visibility, Ident)> {
+        Ok((&self.visibility, self.template.format_ident(field, span)?))
     }
 }
 
@@ -1,10 +1,12 @@
 use proc_macro2::Ident;
-use syn::{Error, Token};
+use syn::{Error, LitInt, parenthesized, Path, Token};
 use syn::ext::IdentExt;
 use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
 
 use crate::pack_bools::config::global::{
-    Config, FieldName, GenType, PackingStrategy, UpdateVisibilityTemplate,
+    BitOrder, Config, DebugImpl, FieldName, FieldOrder, GenType, PackingStrategy, SerdeFormat,
+    StorageMode, UpdateVisibilityTemplate,
 };
 
 pub enum Modifier {
@@ -12,9 +14,26 @@ pub enum Modifier {
     Setters(UpdateVisibilityTemplate),
     NoGetters,
     NoSetters,
+    With(Option<UpdateVisibilityTemplate>),
     Type(PackingStrategy),
     GenType(GenType),
     Field(FieldName),
+    Position(usize),
+    StrictBits,
+    Reserve(usize),
+    BitOrder(BitOrder),
+    FieldOrder(FieldOrder),
+    StorageMode(StorageMode),
+    Ordering(Ident),
+    FlagEnum(Option<Ident>),
+    Debug(DebugImpl),
+    NewTypeDerives(Vec<Path>),
+    Serde(SerdeFormat),
+    Unpacked(Option<Ident>),
+    UnpackedDerives(Vec<Path>),
+    UnpackedHelpers(Vec<Ident>),
+    Constructor(Option<Ident>),
+    RawBits(Option<Ident>),
 }
 
 impl Modifier {
@@ -24,9 +43,31 @@ impl Modifier {
             Modifier::NoGetters => target.skip_getter = true,
             Modifier::Setters(s) => s.update(&mut target.setter),
             Modifier::NoSetters => target.skip_setter = true,
+            Modifier::With(w) => {
+                target.skip_with = false;
+                if let Some(w) = w {
+                    w.update(&mut target.with);
+                }
+            }
             Modifier::Type(t) => target.packed_type = t,
             Modifier::GenType(gt) => target.gen_type = gt,
             Modifier::Field(f) => target.field_name = f,
+            Modifier::Position(p) => target.position = Some(p),
+            Modifier::StrictBits => target.strict_bits = true,
+            Modifier::Reserve(r) => target.reserve = r,
+            Modifier::BitOrder(o) => target.bit_order = o,
+            Modifier::FieldOrder(o) => target.field_order = o,
+            Modifier::StorageMode(m) => target.storage_mode = m,
+            Modifier::Ordering(o) => target.ordering = Some(o),
+            Modifier::FlagEnum(f) => target.flag_enum = Some(f),
+            Modifier::Debug(d) => target.debug = Some(d),
+            Modifier::NewTypeDerives(d) => target.newtype_derives = Some(d),
+            Modifier::Serde(s) => target.serde = Some(s),
+            Modifier::Unpacked(u) => target.unpacked = Some(u),
+            Modifier::UnpackedDerives(d) => target.unpacked_derives = Some(d),
+            Modifier::UnpackedHelpers(h) => target.unpacked_helpers.extend(h),
+            Modifier::Constructor(c) => target.constructor = Some(c),
+            Modifier::RawBits(r) => target.raw_bits = Some(r),
         }
     }
 }
@@ -38,7 +79,8 @@ impl Parse for Modifier {
             'no_get'/'no_getter'/'no_getters', \
             'set'/'setter'/'setters', \
             'no_set'/'no_setter'/'no_setters', \
-            'type', 'inline', 'newtype', 'field'";
+            'with', \
+            'type', 'inline', 'newtype', 'field', 'position', 'strict_bits', 'reserve', 'order', 'storage', 'ordering', 'flags', 'debug', 'newtype_derive', 'serde', 'unpacked', 'derive_unpacked', 'unpacked_helpers', 'new', 'raw_bits'";
         let ident: Ident = Ident::parse_any(input).map_err(|e| Error::new(e.span(), error_msg))?;
         let ret = match ident.to_string().as_str() {
             "getters" | "getter" | "get" => {
@@ -51,6 +93,15 @@ impl Parse for Modifier {
                 let at = input.parse()?;
                 Modifier::Setters(at)
             }
+            "with" => {
+                let lookahead = input.lookahead1();
+                if lookahead.peek(Token![=]) {
+                    let _: Token![=] = input.parse()?;
+                    Modifier::With(Some(input.parse()?))
+                } else {
+                    Modifier::With(None)
+                }
+            }
             "no_get" | "no_getter" | "no_getters" => Modifier::NoGetters,
             "no_set" | "no_setter" | "no_setters" => Modifier::NoSetters,
             "type" => {
@@ -69,11 +120,167 @@ impl Parse for Modifier {
                     Modifier::GenType(GenType::NewType(None))
                 }
             }
+            "newtype_derive" => {
+                let content;
+                parenthesized!(content in input);
+                let derives = Punctuated::<Path, Token![,]>::parse_terminated(&content)?;
+                Modifier::NewTypeDerives(derives.into_iter().collect())
+            }
+            "derive_unpacked" => {
+                let content;
+                parenthesized!(content in input);
+                let derives = Punctuated::<Path, Token![,]>::parse_terminated(&content)?;
+                Modifier::UnpackedDerives(derives.into_iter().collect())
+            }
+            "unpacked_helpers" => {
+                let content;
+                parenthesized!(content in input);
+                let helpers = Punctuated::<Ident, Token![,]>::parse_terminated(&content)?;
+                Modifier::UnpackedHelpers(helpers.into_iter().collect())
+            }
             "field" => {
                 let _: Token![=] = input.parse()?;
                 let f: FieldName = input.parse()?;
                 Modifier::Field(f)
             }
+            "position" => {
+                let _: Token![=] = input.parse()?;
+                let lit: LitInt = input.parse()?;
+                Modifier::Position(lit.base10_parse()?)
+            }
+            "strict_bits" => Modifier::StrictBits,
+            "flags" => {
+                let lookahead = input.lookahead1();
+                if lookahead.peek(Token![=]) {
+                    let _: Token![=] = input.parse()?;
+                    Modifier::FlagEnum(Some(input.parse()?))
+                } else {
+                    Modifier::FlagEnum(None)
+                }
+            }
+            "new" => {
+                let lookahead = input.lookahead1();
+                if lookahead.peek(Token![=]) {
+                    let _: Token![=] = input.parse()?;
+                    Modifier::Constructor(Some(input.parse()?))
+                } else {
+                    Modifier::Constructor(None)
+                }
+            }
+            "raw_bits" => {
+                let lookahead = input.lookahead1();
+                if lookahead.peek(Token![=]) {
+                    let _: Token![=] = input.parse()?;
+                    Modifier::RawBits(Some(input.parse()?))
+                } else {
+                    Modifier::RawBits(None)
+                }
+            }
+            "unpacked" => {
+                let lookahead = input.lookahead1();
+                if lookahead.peek(Token![=]) {
+                    let _: Token![=] = input.parse()?;
+                    Modifier::Unpacked(Some(input.parse()?))
+                } else {
+                    Modifier::Unpacked(None)
+                }
+            }
+            "debug" => {
+                let lookahead = input.lookahead1();
+                if lookahead.peek(Token![=]) {
+                    let _: Token![=] = input.parse()?;
+                    let target = Ident::parse_any(input)?;
+                    match target.to_string().as_str() {
+                        "newtype" => Modifier::Debug(DebugImpl::NewType),
+                        "struct" => Modifier::Debug(DebugImpl::Struct),
+                        _ => {
+                            return Err(Error::new(
+                                target.span(),
+                                "Debug must be newtype or struct",
+                            ))
+                        }
+                    }
+                } else {
+                    Modifier::Debug(DebugImpl::NewType)
+                }
+            }
+            "serde" => {
+                if !cfg!(feature = "serde") {
+                    return Err(Error::new(
+                        ident.span(),
+                        "#[pack_bools(serde)] requires the `serde` feature of pack_bools",
+                    ));
+                }
+                let lookahead = input.lookahead1();
+                if lookahead.peek(Token![=]) {
+                    let _: Token![=] = input.parse()?;
+                    let format: Ident = input.parse()?;
+                    match format.to_string().as_str() {
+                        "fields" => Modifier::Serde(SerdeFormat::Fields),
+                        "compact" => Modifier::Serde(SerdeFormat::Compact),
+                        _ => {
+                            return Err(Error::new(
+                                format.span(),
+                                "Serde format must be fields or compact",
+                            ))
+                        }
+                    }
+                } else {
+                    Modifier::Serde(SerdeFormat::Fields)
+                }
+            }
+            "storage" => {
+                let _: Token![=] = input.parse()?;
+                let mode: Ident = input.parse()?;
+                match mode.to_string().as_str() {
+                    "plain" => Modifier::StorageMode(StorageMode::Plain),
+                    "atomic" => Modifier::StorageMode(StorageMode::Atomic),
+                    "cell" => Modifier::StorageMode(StorageMode::Cell),
+                    "volatile" => Modifier::StorageMode(StorageMode::Volatile),
+                    _ => {
+                        return Err(Error::new(
+                            mode.span(),
+                            "Storage must be plain, atomic, cell or volatile",
+                        ))
+                    }
+                }
+            }
+            "ordering" => {
+                let _: Token![=] = input.parse()?;
+                let ordering: Ident = input.parse()?;
+                match ordering.to_string().as_str() {
+                    "Relaxed" | "Release" | "Acquire" | "AcqRel" | "SeqCst" => {
+                        Modifier::Ordering(ordering)
+                    }
+                    _ => {
+                        return Err(Error::new(
+                            ordering.span(),
+                            "Ordering must be Relaxed, Release, Acquire, AcqRel or SeqCst",
+                        ))
+                    }
+                }
+            }
+            "reserve" => {
+                let _: Token![=] = input.parse()?;
+                let lit: LitInt = input.parse()?;
+                Modifier::Reserve(lit.base10_parse()?)
+            }
+            "order" => {
+                let _: Token![=] = input.parse()?;
+                let order: Ident = input.parse()?;
+                match order.to_string().as_str() {
+                    "lsb0" => Modifier::BitOrder(BitOrder::Lsb0),
+                    "msb0" => Modifier::BitOrder(BitOrder::Msb0),
+                    "declaration" => Modifier::FieldOrder(FieldOrder::Declaration),
+                    "alphabetical" => Modifier::FieldOrder(FieldOrder::Alphabetical),
+                    _ => {
+                        return Err(Error::new(
+                            order.span(),
+                            "Order must be lsb0, msb0, declaration or alphabetical",
+                        ))
+                    }
+                }
+            }
             _ => return Err(Error::new(ident.span(), error_msg)),
         };
 
@@ -1,6 +1,7 @@
-use syn::{Error, Ident, Token};
+use syn::{bracketed, Error, Ident, LitInt, Token};
 use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
+use syn::token::Bracket;
 
 use crate::pack_bools::config::global::{
     Config, FieldName, GenType, PackedType, PackingStrategy, Template, UpdateVisibilityTemplate,
@@ -44,6 +45,32 @@ impl Parse for Template {
 }
 
 impl Parse for PackingStrategy {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Bracket) {
+            let content;
+            bracketed!(content in input);
+            let ty: PackedType = content.parse()?;
+            let _: Token![;] = content.parse()?;
+            let words: LitInt = content.parse()?;
+            let words = words.base10_parse()?;
+            if words == 0 {
+                return Err(Error::new(
+                    content.span(),
+                    "The array must contain at least one word",
+                ));
+            }
+            return Ok(PackingStrategy::FixedArray(ty, words));
+        }
+        let token: Ident = input.fork().parse()?;
+        if token == "auto" {
+            let _: Ident = input.parse()?;
+            return Ok(PackingStrategy::Auto);
+        }
+        Ok(PackingStrategy::FixedType(input.parse()?))
+    }
+}
+
+impl Parse for PackedType {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let token: Ident = input.parse()?;
         let ty = match token.to_string().as_str() {
@@ -52,15 +79,12 @@ impl Parse for PackingStrategy {
             "u32" => PackedType::U32,
             "u64" => PackedType::U64,
             "u128" => PackedType::U128,
-            "auto" => return Ok(PackingStrategy::Auto),
-            _ => {
-                return Err(Error::new(
-                    token.span(),
-                    "Type must be auto, u8, u16, u32, u64 or u128",
-                ))
-            }
+            _ => return Err(Error::new(
+                token.span(),
+                "Type must be auto, u8, u16, u32, u64, u128 or an array of those such as [u64; 4]",
+            )),
         };
-        Ok(PackingStrategy::FixedType(ty))
+        Ok(ty)
     }
 }
 
@@ -121,8 +145,12 @@ impl Parse for GenType {
 
 impl Parse for Config {
     fn parse(input: ParseStream) -> syn::Result<Self> {
+        let options_span = (!input.is_empty()).then(|| input.span());
         let modifiers = Punctuated::<Modifier, Token![,]>::parse_terminated(input)?;
-        let mut default = Config::default();
+        let mut default = Config {
+            options_span,
+            ..Config::default()
+        };
         for modifier in modifiers {
             modifier.modify(&mut default);
         }
@@ -9,8 +9,16 @@ mod parse;
 pub struct Config {
     pub getter: Accessor,
     pub setter: Accessor,
+    pub with: Accessor,
     pub skip: bool,
-    pub default: bool,
+    pub default: DefaultValue,
+    pub name: Option<Ident>,
+    pub bits: Option<u8>,
+    pub enumeration: bool,
+    /// The bit this field is pinned to
+    pub bit: Option<usize>,
+    /// Whether this field only reserves its bits, without accessors
+    pub reserved: bool,
 }
 
 impl Default for Config {
@@ -18,12 +26,27 @@ impl Default for Config {
         Self {
             getter: Accessor::Default,
             setter: Accessor::Default,
+            with: Accessor::Default,
             skip: false,
-            default: false,
+            default: DefaultValue::Unset,
+            name: None,
+            bits: None,
+            enumeration: false,
+            bit: None,
+            reserved: false,
         }
     }
 }
 
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum DefaultValue {
+    /// No default value was given, meaning `false` for bools and `None` for `Option<bool>`
+    #[default]
+    Unset,
+    None,
+    Bool(bool),
+}
+
 #[derive(Debug, Default)]
 pub enum Accessor {
     #[default]
@@ -1,9 +1,9 @@
 use proc_macro2::Ident;
-use syn::{Error, Token};
+use syn::{Error, LitInt, Token};
 use syn::ext::IdentExt;
 use syn::parse::{Parse, ParseStream};
 
-use crate::pack_bools::config::local::{Accessor, Config, VisibilityIdent};
+use crate::pack_bools::config::local::{Accessor, Config, DefaultValue, VisibilityIdent};
 
 #[derive(Debug)]
 pub enum Modifier {
@@ -12,7 +12,14 @@ pub enum Modifier {
     NoGetter,
     Setter(VisibilityIdent),
     NoSetter,
-    SetDefault(bool),
+    With(VisibilityIdent),
+    NoWith,
+    SetDefault(DefaultValue),
+    Name(Ident),
+    Bits(u8),
+    Enum,
+    Bit(usize),
+    Reserved,
 }
 
 impl Modifier {
@@ -23,7 +30,14 @@ impl Modifier {
             Modifier::NoGetter => target.getter = Accessor::Skip,
             Modifier::Setter(s) => target.setter = Accessor::Custom(s),
             Modifier::NoSetter => target.setter = Accessor::Skip,
+            Modifier::With(w) => target.with = Accessor::Custom(w),
+            Modifier::NoWith => target.with = Accessor::Skip,
             Modifier::SetDefault(v) => target.default = v,
+            Modifier::Name(n) => target.name = Some(n),
+            Modifier::Bits(b) => target.bits = Some(b),
+            Modifier::Enum => target.enumeration = true,
+            Modifier::Bit(b) => target.bit = Some(b),
+            Modifier::Reserved => target.reserved = true,
         }
     }
 }
@@ -36,7 +50,8 @@ impl Parse for Modifier {
             'no_get'/'no_getter', \
             'set'/'setter', \
             'no_set'/'no_setter', \
-            'default'";
+            'with', 'no_with', \
+            'default', 'name', 'bits', 'enum', 'bit', 'reserved'";
         let ident: Ident = Ident::parse_any(input).map_err(|e| Error::new(e.span(), error_msg))?;
         let ret = match ident.to_string().as_str() {
             "getter" | "get" => {
@@ -49,24 +64,48 @@ impl Parse for Modifier {
                 let at = input.parse()?;
                 Modifier::Setter(at)
             }
+            "with" => {
+                let _: Token![=] = input.parse()?;
+                let at = input.parse()?;
+                Modifier::With(at)
+            }
             "no_get" | "no_getter" => Modifier::NoGetter,
             "no_set" | "no_setter" => Modifier::NoSetter,
+            "no_with" => Modifier::NoWith,
             "skip" => Modifier::Skip,
+            "enum" => Modifier::Enum,
+            "reserved" => Modifier::Reserved,
             "default" => {
                 let _: Token![=] = input.parse()?;
                 let ident = Ident::parse_any(input)?;
                 let def = match ident.to_string().as_str() {
-                    "true" => true,
-                    "false" => false,
+                    "true" => DefaultValue::Bool(true),
+                    "false" => DefaultValue::Bool(false),
+                    "none" => DefaultValue::None,
                     _ => {
                         return Err(Error::new(
                             ident.span(),
-                            "Expected true/false as default values",
+                            "Expected true/false/none as default values",
                         ))
                     }
                 };
                 Modifier::SetDefault(def)
             }
+            "name" => {
+                let _: Token![=] = input.parse()?;
+                let name = Ident::parse_any(input)?;
+                Modifier::Name(name)
+            }
+            "bits" => {
+                let _: Token![=] = input.parse()?;
+                let lit: LitInt = input.parse()?;
+                Modifier::Bits(lit.base10_parse()?)
+            }
+            "bit" => {
+                let _: Token![=] = input.parse()?;
+                let lit: LitInt = input.parse()?;
+                Modifier::Bit(lit.base10_parse()?)
+            }
             _ => return Err(Error::new(ident.span(), error_msg)),
         };
 
@@ -1,12 +1,21 @@
-use proc_macro2::TokenStream;
-use quote::{format_ident, quote};
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{format_ident, quote, ToTokens};
 use syn::Visibility as Vis;
 use syn::VisRestricted;
 
+pub use global::BitOrder;
 pub use global::Config as GlobalConfig;
+pub use global::DebugImpl;
+pub use global::FieldOrder;
 pub use global::GenType;
+pub use global::PackedType;
+pub use global::SerdeFormat;
+pub use global::Storage;
+pub use global::StorageMode;
 pub use local::Config as LocalConfig;
+pub use local::DefaultValue;
 
+use crate::pack_bools::config::global::VisibilityTemplate;
 use crate::pack_bools::config::local::Accessor;
 
 mod global;
@@ -38,6 +47,34 @@ impl Visibility {
     }
 }
 
+/// The visibility and name of a generated accessor, emitted as `vis fn name`
+pub struct AccessorSignature {
+    pub vis: TokenStream,
+    pub ident: Ident,
+}
+
+impl AccessorSignature {
+    /// The same signature with `suffix` appended to the name
+    pub fn with_suffix(&self, suffix: &str) -> Self {
+        self.with_ident(format_ident!("{}{suffix}", self.ident))
+    }
+
+    /// The same signature with another name
+    pub fn with_ident(&self, ident: Ident) -> Self {
+        Self {
+            vis: self.vis.clone(),
+            ident,
+        }
+    }
+}
+
+impl ToTokens for AccessorSignature {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let Self { vis, ident } = self;
+        tokens.extend(quote! { #vis fn #ident });
+    }
+}
+
 pub struct CombinedConfig<'a> {
     global: &'a GlobalConfig,
     local: &'a LocalConfig,
@@ -48,57 +85,84 @@ impl<'a> CombinedConfig<'a> {
         Self { global, local }
     }
 
-    pub fn getter(&self, field_name: &str, inh: &Vis) -> Option<TokenStream> {
-        let (vis, ident) = match &self.local.getter {
-            Accessor::Custom(custom) => {
-                let (vis, ident) = custom.get_parts();
-                let ident = if let Some(ident) = ident {
-                    ident.clone()
-                } else {
-                    let s = self.global.getter.template.format(field_name);
-                    format_ident!("{s}")
-                };
-                (vis, ident)
-            }
-            Accessor::Default => {
-                // We use the global config, check if we should generate first
-                if self.global.skip_getter {
-                    return None;
-                }
-                self.global.getter.get_formatted_parts(field_name)
-            }
-            Accessor::Skip => {
-                return None;
-            }
-        };
-        let vis = vis.to_visibility(inh);
-        Some(quote! {#vis fn #ident})
+    pub fn getter(
+        &self,
+        field_name: &str,
+        inh: &Vis,
+        span: Span,
+    ) -> syn::Result<Option<AccessorSignature>> {
+        accessor(
+            &self.local.getter,
+            &self.global.getter,
+            self.global.skip_getter,
+            field_name,
+            inh,
+            span,
+        )
     }
 
-    pub fn setter(&self, field_name: &str, inh: &Vis) -> Option<TokenStream> {
-        let (vis, ident) = match &self.local.setter {
-            Accessor::Custom(custom) => {
-                let (vis, ident) = custom.get_parts();
-                let ident = if let Some(ident) = ident {
-                    ident.clone()
-                } else {
-                    let s = self.global.setter.template.format(field_name);
-                    format_ident!("{s}")
-                };
-                (vis, ident)
-            }
-            Accessor::Default => {
-                // We use the global config, check if we should generate first
-                if self.global.skip_setter {
-                    return None;
-                }
-                self.global.setter.get_formatted_parts(field_name)
-            }
-            Accessor::Skip => {
-                return None;
-            }
-        };
-        let vis = vis.to_visibility(inh);
-        Some(quote! {#vis fn #ident})
+    pub fn setter(
+        &self,
+        field_name: &str,
+        inh: &Vis,
+        span: Span,
+    ) -> syn::Result<Option<AccessorSignature>> {
+        accessor(
+            &self.local.setter,
+            &self.global.setter,
+            self.global.skip_setter,
+            field_name,
+            inh,
+            span,
+        )
+    }
+
+    pub fn with(
+        &self,
+        field_name: &str,
+        inh: &Vis,
+        span: Span,
+    ) -> syn::Result<Option<AccessorSignature>> {
+        accessor(
+            &self.local.with,
+            &self.global.with,
+            self.global.skip_with,
+            field_name,
+            inh,
+            span,
+        )
     }
 }
+
+fn accessor(
+    local: &Accessor,
+    global: &VisibilityTemplate,
+    skip_global: bool,
+    field_name: &str,
+    inh: &Vis,
+    span: Span,
+) -> syn::Result<Option<AccessorSignature>> {
+    let (vis, ident) = match local {
+        Accessor::Custom(custom) => {
+            let (vis, ident) = custom.get_parts();
+            let ident = if let Some(ident) = ident {
+                ident.clone()
+            } else {
+                global.template.format_ident(field_name, span)?
+            };
+            (vis, ident)
+        }
+        Accessor::Default => {
+            // We use the global config, check if we should generate first
+            if skip_global {
+                return Ok(None);
+            }
+            global.get_formatted_parts(field_name, span)?
+        }
+        Accessor::Skip => {
+            return Ok(None);
+        }
+    };
+    let vis = vis.to_visibility(inh);
+    Ok(Some(AccessorSignature { vis, ident }))
+}
@@ -0,0 +1,136 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::{Attribute, Generics, parse_quote, Path, Token};
+use syn::ext::IdentExt;
+use syn::punctuated::Punctuated;
+
+use crate::pack_bools::config::{DebugImpl, GlobalConfig, Storage};
+use crate::pack_bools::fields::{DeclaredField, SplitFields};
+use crate::pack_bools::storage::Access;
+
+/// Returns the `Debug` impl of the newtype, listing the packed fields by name, if requested
+pub fn newtype_debug(
+    config: &GlobalConfig,
+    newtype: &TokenStream,
+    split: &SplitFields,
+    storage: Storage,
+) -> Option<TokenStream> {
+    if config.debug != Some(DebugImpl::NewType) {
+        return None;
+    }
+    let access = Access::new(config);
+    let name = newtype.to_string();
+    let fields = split
+        .to_convert
+        .iter()
+        .filter(|packed| !packed.local.reserved)
+        .map(|packed| {
+            let name = packed.external_name();
+            let value = packed.value(storage, access, &quote! { self.0 });
+            quote! { .field(#name, &(#value)) }
+        });
+    Some(quote! {
+        impl ::core::fmt::Debug for #newtype {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.debug_struct(#name)
+                    #(#fields)*
+                    .finish()
+            }
+        }
+    })
+}
+
+/// Returns the `Debug` impl of the struct, listing all fields in declaration order with the packed
+/// fields in place of the bitflags, if requested. The derived `Debug` impl is removed from `attrs`.
+pub fn struct_debug(
+    config: &GlobalConfig,
+    attrs: &mut [Attribute],
+    ident: &Ident,
+    generics: &Generics,
+    split: &SplitFields,
+    storage: Storage,
+    packed_path: &TokenStream,
+) -> syn::Result<Option<TokenStream>> {
+    if config.debug != Some(DebugImpl::Struct) {
+        return Ok(None);
+    }
+    strip_derive_debug(attrs)?;
+
+    let access = Access::new(config);
+    let packed = quote! { self.#packed_path };
+    let fields = split.declared.iter().map(|declared| match *declared {
+        DeclaredField::Kept(idx) => {
+            let field = &split.to_keep[idx];
+            match &field.ident {
+                Some(field_ident) => {
+                    let name = field_ident.unraw().to_string();
+                    quote! { .field(#name, &self.#field_ident) }
+                }
+                None => {
+                    let member = split.kept_member(idx);
+                    quote! { .field(&self.#member) }
+                }
+            }
+        }
+        DeclaredField::Packed(idx) => {
+            let packed_field = &split.to_convert[idx];
+            let value = packed_field.value(storage, access, &packed);
+            if split.is_tuple {
+                quote! { .field(&(#value)) }
+            } else {
+                let name = packed_field.external_name();
+                quote! { .field(#name, &(#value)) }
+            }
+        }
+    });
+    let name = ident.unraw().to_string();
+    let debug = if split.is_tuple {
+        quote! { debug_tuple }
+    } else {
+        quote! { debug_struct }
+    };
+
+    // Like derived impls, require every type parameter to implement `Debug`
+    let mut generics = generics.clone();
+    let params: Vec<_> = generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect();
+    let where_clause = generics.make_where_clause();
+    for param in params {
+        where_clause
+            .predicates
+            .push(parse_quote! { #param: ::core::fmt::Debug });
+    }
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+    Ok(Some(quote! {
+        impl #impl_generics ::core::fmt::Debug for #ident #type_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.#debug(#name)
+                    #(#fields)*
+                    .finish()
+            }
+        }
+    }))
+}
+
+/// Removes `Debug` from the `#[derive(..)]` attributes, as it is replaced by a generated impl
+fn strip_derive_debug(attrs: &mut [Attribute]) -> syn::Result<()> {
+    for attr in attrs
+        .iter_mut()
+        .filter(|attr| attr.path().is_ident("derive"))
+    {
+        let derives = attr.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)?;
+        let derives: Punctuated<Path, Token![,]> = derives
+            .into_iter()
+            .filter(|path| {
+                path.segments
+                    .last()
+                    .is_none_or(|segment| segment.ident != "Debug")
+            })
+            .collect();
+        *attr = parse_quote! { #[derive(#derives)] };
+    }
+    Ok(())
+}
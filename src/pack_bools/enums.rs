@@ -0,0 +1,367 @@
+use proc_macro2::{Group, Ident, TokenStream};
+use quote::{quote, ToTokens};
+use syn::spanned::Spanned;
+use syn::token::Paren;
+use syn::{Error, Expr, ExprLit, Fields, ItemEnum, Lit, parse2, Variant};
+
+use crate::pack_bools::config::{GlobalConfig, PackedType, Storage};
+use crate::pack_bools::{default_impl, packed_type};
+use crate::pack_bools::fields::{Accessor, PackedField, Receiver, SplitFields};
+use crate::pack_bools::storage::Access;
+
+/// An accessor shared by all variants having a field with the same accessor name
+struct EnumAccessor {
+    accessor: Accessor,
+    /// The variant the accessor was first generated for
+    variant: Ident,
+    /// The docs of the accessor for each variant
+    docs: Vec<String>,
+    arms: Vec<TokenStream>,
+}
+
+fn push_arm(
+    accessors: &mut Vec<EnumAccessor>,
+    variant: &Ident,
+    packed: &PackedField,
+    accessor: Accessor,
+    arm: TokenStream,
+) -> syn::Result<()> {
+    let key = accessor.signature.to_token_stream().to_string();
+    let doc = format!("For `{variant}`: {}", accessor.doc);
+    let Some(shared) = accessors
+        .iter_mut()
+        .find(|shared| shared.accessor.signature.to_token_stream().to_string() == key)
+    else {
+        accessors.push(EnumAccessor {
+            accessor,
+            variant: variant.clone(),
+            docs: vec![doc],
+            arms: vec![arm],
+        });
+        return Ok(());
+    };
+    let same_type = |a: &Option<TokenStream>, b: &Option<TokenStream>| {
+        a.as_ref().map(ToString::to_string) == b.as_ref().map(ToString::to_string)
+    };
+    if !same_type(&shared.accessor.ty, &accessor.ty)
+        || shared.accessor.params.to_string() != accessor.params.to_string()
+    {
+        return Err(Error::new(
+            packed.field.span(),
+            format!(
+                "fields with the same name in different variants must have the same type, but \
+                `{}` has another type in `{}`",
+                packed.name, shared.variant
+            ),
+        ));
+    }
+    shared.docs.push(doc);
+    shared.arms.push(arm);
+    Ok(())
+}
+
+pub fn pack_enum(config: GlobalConfig, definition: ItemEnum) -> TokenStream {
+    if !definition.variants.is_empty()
+        && definition
+            .variants
+            .iter()
+            .all(|variant| matches!(variant.fields, Fields::Unit))
+    {
+        if let Some(span) = config.options_span {
+            return Error::new(
+                span,
+                "#[pack_bools(..)] options can't be used on fieldless enums, which only get the \
+                conversions used by #[pack_bools(enum)] fields",
+            )
+            .to_compile_error();
+        }
+        let mapping = try_syn!(discriminant_mapping(&definition));
+        return quote! {
+            #definition
+
+            #mapping
+        };
+    }
+
+    let ItemEnum {
+        attrs,
+        vis,
+        enum_token,
+        ident,
+        generics,
+        brace_token: _,
+        variants,
+    } = definition;
+
+    let mut split_variants = Vec::with_capacity(variants.len());
+    for variant in variants {
+        let Variant {
+            attrs,
+            ident,
+            fields,
+            discriminant,
+        } = variant;
+        let split = if let Fields::Unit = fields {
+            None
+        } else {
+            let split = try_syn!(SplitFields::new(fields, &config, ident.span()));
+            if let Some(width) = config.packed_type.fixed_bit_width() {
+                try_syn!(split.check_pinned(width));
+            }
+            Some(split)
+        };
+        split_variants.push((attrs, ident, split, discriminant));
+    }
+
+    let max_packed = split_variants
+        .iter()
+        .filter_map(|(_, _, split, _)| split.as_ref())
+        .map(|split| split.bits)
+        .max()
+        .unwrap_or(0);
+    let inner_type = try_syn!(config.packed_type.to_storage(
+        config.storage_mode,
+        max_packed,
+        ident.span(),
+        |word| {
+            let mut max_packed = 0;
+            for (_, _, split, _) in &mut split_variants {
+                if let Some(split) = split {
+                    max_packed = max_packed.max(split.align(word)?);
+                }
+            }
+            Ok(max_packed)
+        }
+    ));
+    let (packed_type, newtype) = try_syn!(packed_type(&config, &attrs, &ident, inner_type));
+    let default_impl = default_impl(&config, &packed_type, inner_type, &[]);
+
+    // The bitflags are bound by reference in match arms, and both indexing an array and calling methods
+    // on atomics auto-deref
+    let packed_path = match (config.gen_type.is_inline(), inner_type) {
+        (true, Storage::Int(_)) if !config.storage_mode.is_shared() => quote! { *packed },
+        (true, _) => quote! { packed },
+        (false, _) => quote! { packed.0 },
+    };
+
+    let mut accessors = vec![];
+    for (_, variant, split, _) in &split_variants {
+        let Some(split) = split else {
+            continue;
+        };
+        let member = &split.member;
+        for packed in &split.to_convert {
+            if !packed.default_bits(inner_type).is_empty() {
+                return Error::new(
+                    packed.field.span(),
+                    "#[pack_bools(default = ..)] is not available on enum variants",
+                )
+                .to_compile_error();
+            }
+
+            for accessor in try_syn!(packed.accessors(
+                &config,
+                &vis,
+                &packed_path,
+                inner_type,
+                Access::new(&config)
+            )) {
+                if accessor.receiver == Receiver::Value {
+                    return Error::new(
+                        packed.field.span(),
+                        "#[pack_bools(with = ..)] is not available on enum variants",
+                    )
+                    .to_compile_error();
+                }
+                let body = &accessor.body;
+                let arm = if accessor.ty.is_some() {
+                    quote! {
+                        Self::#variant { #member: packed, .. } => ::core::option::Option::Some({ #body }),
+                    }
+                } else {
+                    quote! {
+                        Self::#variant { #member: packed, .. } => {
+                            #body
+                            true
+                        }
+                    }
+                };
+                try_syn!(push_arm(&mut accessors, variant, packed, accessor, arm));
+            }
+        }
+    }
+
+    let accessors = accessors
+        .into_iter()
+        .map(|EnumAccessor { accessor, docs, arms, .. }| {
+            let Accessor {
+                doc: _,
+                signature,
+                receiver,
+                is_const: _,
+                params,
+                ty,
+                body: _,
+            } = accessor;
+            if let Some(ty) = ty {
+                quote! {
+                    #(
+                        #[doc = #docs]
+                        #[doc = ""]
+                    )*
+                    /// Returns `None` for the other variants.
+                    #[allow(unreachable_patterns)]
+                    #signature (#receiver, #params) -> ::core::option::Option<#ty> {
+                        match self {
+                            #(#arms)*
+                            _ => ::core::option::Option::None,
+                        }
+                    }
+                }
+            } else {
+                quote! {
+                    #(
+                        #[doc = #docs]
+                        #[doc = ""]
+                    )*
+                    /// Returns whether the variant has the field, doing nothing for the other variants.
+                    #[allow(unreachable_patterns)]
+                    #signature (#receiver, #params) -> bool {
+                        match self {
+                            #(#arms)*
+                            _ => false,
+                        }
+                    }
+                }
+            }
+        });
+
+    let variants = split_variants
+        .into_iter()
+        .map(|(attrs, ident, split, discriminant)| {
+            let fields = split.map(|split| {
+                let packed_type = (!split.to_convert.is_empty()).then_some(&packed_type);
+                split.into_fields(packed_type, &TokenStream::new())
+            });
+            let discriminant = discriminant.map(|(eq, expr)| quote! { #eq #expr });
+            quote! {
+                #(#attrs)* #ident #fields #discriminant
+            }
+        });
+
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        #(#attrs)* #vis #enum_token #ident #generics #where_clause {
+            #(#variants),*
+        }
+
+        #newtype
+
+        #default_impl
+
+        impl #impl_generics #ident #type_generics #where_clause {
+            #(#accessors)*
+        }
+    }
+}
+
+/// Generates the conversions used by `#[pack_bools(enum, bits = ..)]` fields for a fieldless enum: a
+/// `From` impl into each unsigned type its discriminants fit in and a `TryFrom` impl back, returning the
+/// invalid value as the error. These are its `#[repr]` type (or `u8` if there is none) and the wider
+/// types, along with the narrower types if the discriminants are integer literals fitting in them.
+fn discriminant_mapping(definition: &ItemEnum) -> syn::Result<TokenStream> {
+    let ident = &definition.ident;
+    let mut repr = None;
+    for attr in &definition.attrs {
+        if attr.path().is_ident("repr") {
+            attr.parse_nested_meta(|meta| {
+                if let Some(ident) = meta.path.get_ident() {
+                    if let Ok(ty) = parse2::<PackedType>(ident.to_token_stream()) {
+                        repr = Some(ty);
+                    }
+                }
+                if meta.input.peek(Paren) {
+                    // Skip arguments such as in `align(4)`
+                    let _: Group = meta.input.parse()?;
+                }
+                Ok(())
+            })?;
+        }
+    }
+
+    let variants: Vec<_> = definition
+        .variants
+        .iter()
+        .map(|variant| &variant.ident)
+        .collect();
+    let max = max_discriminant(definition);
+    let smallest = match max {
+        Some(max) => {
+            let bits = (u128::BITS - max.leading_zeros()).max(1) as usize;
+            PackedType::smallest_larger_than(bits).unwrap()
+        }
+        None => repr.unwrap_or(PackedType::U8),
+    };
+    // Without a `#[repr]` or literal discriminants, the discriminants are `isize` and have to be checked
+    // to fit in a `u8`
+    let checks = (repr.is_none() && max.is_none()).then(|| {
+        let msg = format!("discriminants of `{ident}` must fit in a u8 or be given a #[repr]");
+        quote! {
+            const _: () = {
+                #(::core::assert!(#ident::#variants as isize >= 0 && #ident::#variants as isize <= u8::MAX as isize, #msg);)*
+            };
+        }
+    });
+
+    let impls = smallest.and_wider().map(|repr| {
+        quote! {
+            impl ::core::convert::From<#ident> for #repr {
+                fn from(value: #ident) -> Self {
+                    value as Self
+                }
+            }
+
+            impl ::core::convert::TryFrom<#repr> for #ident {
+                type Error = #repr;
+
+                fn try_from(value: #repr) -> ::core::result::Result<Self, #repr> {
+                    #(
+                        if value == Self::#variants as #repr {
+                            return ::core::result::Result::Ok(Self::#variants);
+                        }
+                    )*
+                    ::core::result::Result::Err(value)
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        #checks
+
+        #(#impls)*
+    })
+}
+
+/// The largest discriminant of a fieldless enum, if all of its discriminants are given as unsigned
+/// integer literals or follow such a discriminant
+fn max_discriminant(definition: &ItemEnum) -> Option<u128> {
+    let mut next = 0u128;
+    let mut max = 0;
+    for variant in &definition.variants {
+        let value = match &variant.discriminant {
+            Some((
+                _,
+                Expr::Lit(ExprLit {
+                    lit: Lit::Int(lit), ..
+                }),
+            )) => lit.base10_parse().ok()?,
+            Some(_) => return None,
+            None => next,
+        };
+        max = max.max(value);
+        next = value.checked_add(1)?;
+    }
+    Some(max)
+}
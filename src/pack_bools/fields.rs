@@ -0,0 +1,1246 @@
+use proc_macro2::{Ident, Literal, Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::{
+    Error, Expr, ExprLit, Field, Fields, GenericArgument, Index, Lit, Member, Meta, parse2,
+    parse_str, PathArguments, Type, Visibility,
+};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+
+use crate::pack_bools::config::{
+    AccessorSignature, BitOrder, CombinedConfig, DefaultValue, FieldOrder, GlobalConfig,
+    LocalConfig, PackedType, Storage, StorageMode,
+};
+use crate::pack_bools::storage::Access;
+
+/// The fields of a struct or an enum variant, split into the fields to pack and the fields to keep
+pub struct SplitFields {
+    pub is_tuple: bool,
+    pub to_keep: Vec<Field>,
+    pub to_convert: Vec<PackedField>,
+    /// The fields in declaration order, as indices into `to_keep` or `to_convert`
+    pub declared: Vec<DeclaredField>,
+    /// The number of bits needed to store all packed fields
+    pub bits: usize,
+    /// Where the field containing the bitflags is placed among the fields to keep
+    pub position: usize,
+    /// The number of unused bits to leave after the packed fields
+    reserve: usize,
+    field_order: FieldOrder,
+    bit_order: BitOrder,
+    pub member: Member,
+}
+
+#[derive(Copy, Clone)]
+pub enum DeclaredField {
+    Kept(usize),
+    Packed(usize),
+}
+
+pub struct PackedField {
+    pub field: Field,
+    pub kind: FieldKind,
+    pub local: LocalConfig,
+    /// The name used when substituting `%` in accessor templates
+    pub name: String,
+    /// The index of the first bit of this field, numbered according to `order`
+    pub offset: usize,
+    pub order: BitOrder,
+}
+
+/// A variant of the flag enum generated for a packed bool, along with the code accessing it
+pub struct Flag {
+    pub variant: Ident,
+    pub doc: String,
+    /// An expression reading the bool
+    pub read: TokenStream,
+    /// Statements writing the bool `value`
+    pub write: TokenStream,
+    /// Statements inverting the bool
+    pub toggle: TokenStream,
+}
+
+/// A method generated for a packed field, without its `self` receiver so that it can be emitted both
+/// for structs and for enums
+pub struct Accessor {
+    pub doc: String,
+    pub signature: AccessorSignature,
+    pub receiver: Receiver,
+    /// Whether the method is a `const fn`
+    pub is_const: bool,
+    /// The parameters following `self`
+    pub params: TokenStream,
+    /// The returned type, `None` for setters returning `()`
+    pub ty: Option<TokenStream>,
+    pub body: TokenStream,
+}
+
+/// How a generated method takes `self`
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum Receiver {
+    Ref,
+    Mut,
+    /// `mut self`, for methods returning the updated value
+    Value,
+}
+
+impl ToTokens for Receiver {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend(match self {
+            Receiver::Ref => quote! { &self },
+            Receiver::Mut => quote! { &mut self },
+            Receiver::Value => quote! { mut self },
+        });
+    }
+}
+
+impl Accessor {
+    /// Emits this accessor as a method taking `self`
+    pub fn into_method(self) -> TokenStream {
+        let Self {
+            doc,
+            signature: AccessorSignature { vis, ident },
+            receiver,
+            is_const,
+            params,
+            ty,
+            body,
+        } = self;
+        let constness = is_const.then(|| quote! { const });
+        let ty = ty.map(|ty| quote! { -> #ty });
+        quote! {
+            #[doc = #doc]
+            #vis #constness fn #ident (#receiver, #params) #ty {
+                #body
+            }
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum FieldKind {
+    Bool,
+    /// An `Option<bool>`, stored in two bits: whether it is `Some`, and the value if so
+    TriState,
+    /// An unsigned integer stored in the given number of bits
+    Int(u8),
+    /// A fieldless enum stored in the given number of bits, converted through the smallest unsigned
+    /// integer type fitting those bits
+    Enum(u8),
+    /// A `[bool; N]`, stored in N consecutive bits
+    Array(usize),
+}
+
+impl FieldKind {
+    pub fn bit_width(self) -> usize {
+        match self {
+            FieldKind::Bool => 1,
+            FieldKind::TriState => 2,
+            FieldKind::Int(bits) | FieldKind::Enum(bits) => bits as usize,
+            FieldKind::Array(len) => len,
+        }
+    }
+
+    /// The kind of fields packed even without a `#[pack_bools]` attribute
+    fn detect(ty: &Type) -> Option<Self> {
+        if is_bool_type(ty) {
+            Some(FieldKind::Bool)
+        } else if is_tristate_type(ty) {
+            Some(FieldKind::TriState)
+        } else {
+            bool_array_len(ty).map(FieldKind::Array)
+        }
+    }
+
+    fn from_field(ty: &Type, config: Option<&LocalConfig>, span: Span) -> syn::Result<Self> {
+        let bits = config.and_then(|config| config.bits);
+        let is_enum = config.is_some_and(|config| config.enumeration);
+        let default = config.map_or(DefaultValue::Unset, |config| config.default);
+        if let Some(kind) = Self::detect(ty) {
+            if bits.is_some() || is_enum {
+                return Err(Error::new(
+                    span,
+                    "#[pack_bools(bits = ..)] and #[pack_bools(enum)] can't be used on bools, Option<bool> or bool arrays",
+                ));
+            }
+            if let (FieldKind::Bool | FieldKind::Array(_), DefaultValue::None) = (kind, default) {
+                return Err(Error::new(
+                    span,
+                    "#[pack_bools(default = none)] can only be used on Option<bool>",
+                ));
+            }
+            return Ok(kind);
+        }
+        let Some(bits) = bits else {
+            return Err(Error::new(
+                span,
+                "#[pack_bools] can only be used on bools, Option<bool> and bool arrays, or on unsigned integers and enums together with `bits`",
+            ));
+        };
+        let width = if is_enum {
+            PackedType::U128.bit_width()
+        } else if let Some(width) = uint_bit_width(ty) {
+            width
+        } else {
+            return Err(Error::new(
+                span,
+                "#[pack_bools(bits = ..)] can only be used on unsigned integers (u8, u16, u32, u64 or u128), use #[pack_bools(enum, bits = ..)] for enums",
+            ));
+        };
+        if bits == 0 || bits > width {
+            return Err(Error::new(
+                span,
+                format!("#[pack_bools(bits = {bits})] must be between 1 and {width} for this type"),
+            ));
+        }
+        if default != DefaultValue::Unset {
+            return Err(Error::new(
+                span,
+                "#[pack_bools(default = ..)] can only be used on bools, Option<bool> and bool arrays",
+            ));
+        }
+        if is_enum {
+            Ok(FieldKind::Enum(bits))
+        } else {
+            Ok(FieldKind::Int(bits))
+        }
+    }
+}
+
+impl SplitFields {
+    pub fn new(fields: Fields, config: &GlobalConfig, span: Span) -> syn::Result<Self> {
+        let is_tuple = matches!(fields, Fields::Unnamed(_));
+        let mut to_keep = vec![];
+        let mut to_convert = vec![];
+        let mut declared = vec![];
+        for (idx, field) in fields.into_iter().enumerate() {
+            match ModifiedField::from_field(field)? {
+                ModifiedField::Excluded(field) => {
+                    declared.push(DeclaredField::Kept(to_keep.len()));
+                    to_keep.push(field);
+                }
+                ModifiedField::PackedField(field, kind, local) => {
+                    declared.push(DeclaredField::Packed(to_convert.len()));
+                    let local = local.map(|local| *local).unwrap_or_default();
+                    let name = field_name(&field, &local, idx);
+                    to_convert.push(PackedField {
+                        field,
+                        kind,
+                        local,
+                        name,
+                        offset: 0,
+                        order: config.bit_order,
+                    });
+                }
+            }
+        }
+
+        if config.strict_bits {
+            if let Some(packed) = to_convert.iter().find(|packed| packed.local.bit.is_none()) {
+                return Err(Error::new(
+                    packed.field.span(),
+                    "#[pack_bools(strict_bits)] requires every packed field to be pinned with #[pack_bools(bit = ..)]",
+                ));
+            }
+        }
+
+        let position = config.position.unwrap_or(to_keep.len());
+        if position > to_keep.len() {
+            return Err(Error::new(
+                span,
+                format!(
+                    "#[pack_bools(position = {position})] is out of range: the packed field can be placed at positions 0 to {}",
+                    to_keep.len()
+                ),
+            ));
+        }
+        let member = if is_tuple {
+            Member::Unnamed(Index::from(position))
+        } else {
+            Member::Named(format_ident!("{}", config.field_name.0))
+        };
+
+        let mut split = Self {
+            is_tuple,
+            to_keep,
+            to_convert,
+            declared,
+            bits: 0,
+            position,
+            reserve: config.reserve,
+            field_order: config.field_order,
+            bit_order: config.bit_order,
+            member,
+        };
+        split.layout(None)?;
+        Ok(split)
+    }
+
+    /// Lays out the packed fields again so that no field other than a `[bool; N]` straddles two words of
+    /// `word` type, and returns the number of bits needed
+    pub fn align(&mut self, word: PackedType) -> syn::Result<usize> {
+        self.layout(Some(word.bit_width() as usize))
+    }
+
+    /// Checks that the fields pinned with `bit` fit in a storage of `width` bits
+    pub fn check_pinned(&self, width: usize) -> syn::Result<()> {
+        for packed in &self.to_convert {
+            if let Some(bit) = packed.local.bit {
+                if bit + packed.kind.bit_width() > width {
+                    return Err(Error::new(
+                        packed.field.span(),
+                        format!(
+                            "#[pack_bools(bit = {bit})] is beyond the {width} bits of the storage"
+                        ),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Assigns the offset of each packed field and returns the number of bits needed. Fields pinned with
+    /// `bit` are placed first, then each other field takes the first free bits large enough for it after
+    /// the previous such field, so that they keep their declaration order. If `word_width` is given, no
+    /// field other than a `[bool; N]` straddles two words of that many bits.
+    fn layout(&mut self, word_width: Option<usize>) -> syn::Result<usize> {
+        let straddles = |kind: FieldKind, start: usize, width: usize| {
+            word_width.is_some_and(|word_width| {
+                !matches!(kind, FieldKind::Array(_)) && start % word_width + width > word_width
+            })
+        };
+
+        // The index of the field occupying each bit
+        let mut owners: Vec<Option<usize>> = vec![];
+        let mut offsets = vec![0; self.to_convert.len()];
+        // Where to start looking for free bits for the next field that isn't pinned
+        let mut cursor = 0;
+        let is_pinned = |idx: &usize| self.to_convert[*idx].local.bit.is_some();
+        let count = self.to_convert.len();
+        let pinned = (0..count).filter(is_pinned);
+        let mut unpinned: Vec<_> = (0..count).filter(|idx| !is_pinned(idx)).collect();
+        if self.field_order == FieldOrder::Alphabetical {
+            unpinned.sort_by(|&a, &b| self.to_convert[a].name.cmp(&self.to_convert[b].name));
+        }
+        for idx in pinned.chain(unpinned) {
+            let packed = &self.to_convert[idx];
+            let kind = packed.kind;
+            let width = kind.bit_width();
+            if let Some(word_width) = word_width {
+                if width > word_width && !matches!(kind, FieldKind::Array(_)) {
+                    return Err(Error::new(
+                        packed.field.span(),
+                        format!(
+                            "`{}` is wider than the {word_width}-bit words of the storage",
+                            packed.name
+                        ),
+                    ));
+                }
+            }
+
+            let start = if let Some(bit) = packed.local.bit {
+                if straddles(kind, bit, width) {
+                    return Err(Error::new(
+                        packed.field.span(),
+                        format!(
+                            "#[pack_bools(bit = {bit})] makes `{}` straddle two {}-bit words of the storage",
+                            packed.name,
+                            word_width.unwrap()
+                        ),
+                    ));
+                }
+                if let Some(other) =
+                    (bit..bit + width).find_map(|bit| owners.get(bit).copied().flatten())
+                {
+                    return Err(Error::new(
+                        packed.field.span(),
+                        format!(
+                            "#[pack_bools(bit = {bit})] overlaps with `{}`",
+                            self.to_convert[other].name
+                        ),
+                    ));
+                }
+                bit
+            } else {
+                let mut start = cursor;
+                while straddles(kind, start, width)
+                    || (start..start + width).any(|bit| matches!(owners.get(bit), Some(Some(_))))
+                {
+                    start += 1;
+                }
+                cursor = start + width;
+                start
+            };
+
+            if owners.len() < start + width {
+                owners.resize(start + width, None);
+            }
+            owners[start..start + width].fill(Some(idx));
+            offsets[idx] = start;
+        }
+
+        for (packed, offset) in self.to_convert.iter_mut().zip(offsets) {
+            packed.offset = offset;
+        }
+        self.bits = owners.len() + self.reserve;
+        Ok(self.bits)
+    }
+
+    /// The indices of all bits assigned to packed fields that aren't reserved, counting from the least
+    /// significant bit of the first word
+    pub fn assigned_bits(&self, storage: Storage) -> Vec<usize> {
+        self.to_convert
+            .iter()
+            .filter(|packed| !packed.local.reserved)
+            .flat_map(|packed| packed.physical_bits(storage))
+            .collect()
+    }
+
+    /// The indices of all bits assigned to packed fields, including reserved fields and the bits left by
+    /// `reserve`, counting from the least significant bit of the first word
+    pub fn known_bits(&self, storage: Storage) -> Vec<usize> {
+        let word_width = storage.word().bit_width() as usize;
+        let reserved = (self.bits - self.reserve..self.bits).map(|offset| {
+            let (word, bit) = physical(storage, self.bit_order, offset, 1);
+            word.unwrap_or(0) * word_width + bit
+        });
+        self.to_convert
+            .iter()
+            .flat_map(|packed| packed.physical_bits(storage))
+            .chain(reserved)
+            .collect()
+    }
+
+    /// The member of the struct or variant holding the kept field at `idx` in `to_keep`, once the field
+    /// containing the bitflags is inserted
+    pub fn kept_member(&self, idx: usize) -> Member {
+        match &self.to_keep[idx].ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => {
+                let index = if idx < self.position { idx } else { idx + 1 };
+                Member::Unnamed(Index::from(index))
+            }
+        }
+    }
+
+    /// Emits the fields enclosed in braces or parentheses, with the field containing the bitflags
+    /// inserted with the attributes `packed_attrs` if `packed_type` is given
+    pub fn into_fields(
+        self,
+        packed_type: Option<&TokenStream>,
+        packed_attrs: &TokenStream,
+    ) -> TokenStream {
+        let Self {
+            is_tuple,
+            to_keep,
+            position,
+            member,
+            ..
+        } = self;
+        let mut fields: Vec<_> = to_keep.into_iter().map(Field::into_token_stream).collect();
+        if let Some(packed_type) = packed_type {
+            fields.insert(
+                position,
+                if is_tuple {
+                    quote! { #packed_attrs #packed_type }
+                } else {
+                    quote! { #packed_attrs #member: #packed_type }
+                },
+            );
+        }
+        if is_tuple {
+            quote! { ( #(#fields),* ) }
+        } else {
+            quote! { { #(#fields),* } }
+        }
+    }
+}
+
+impl PackedField {
+    /// Returns the accessors of this field that should be generated, reading from and writing to the
+    /// bitflags at `packed` through `access`
+    pub fn accessors(
+        &self,
+        config: &GlobalConfig,
+        inh: &Visibility,
+        packed: &TokenStream,
+        storage: Storage,
+        access: Access,
+    ) -> syn::Result<Vec<Accessor>> {
+        let combined = CombinedConfig::new(config, &self.local);
+        let span = self.field.span();
+        let mut accessors = vec![];
+        if self.local.reserved {
+            return Ok(accessors);
+        }
+        let name = &self.name;
+        let location = self.location();
+        if let Some(getter) = combined.getter(&self.name, inh, span)? {
+            if let FieldKind::Array(len) = self.kind {
+                let check = self.index_check(len);
+                let read = self.read_bit(storage, access, packed, &quote! { index });
+                let array_getter = getter.with_suffix("_array");
+                accessors.push(Accessor {
+                    doc: format!("Returns element `index` of `{name}`, {location}. Panics if `index` is out of range."),
+                    signature: getter,
+                    receiver: Receiver::Ref,
+                    is_const: false,
+                    params: quote! { index: usize },
+                    ty: Some(quote! { bool }),
+                    body: quote! {
+                        #check
+                        #read
+                    },
+                });
+                accessors.push(Accessor {
+                    doc: format!("Returns all elements of `{name}`, {location}."),
+                    signature: array_getter,
+                    receiver: Receiver::Ref,
+                    is_const: false,
+                    params: TokenStream::new(),
+                    ty: Some(self.field.ty.to_token_stream()),
+                    body: quote! {
+                        ::core::array::from_fn(|index| #read)
+                    },
+                });
+            } else {
+                accessors.push(Accessor {
+                    doc: format!("Returns `{name}`, {location}."),
+                    signature: getter,
+                    receiver: Receiver::Ref,
+                    is_const: false,
+                    params: TokenStream::new(),
+                    ty: Some(self.getter_type()),
+                    body: self.read(storage, access, packed),
+                });
+            }
+        }
+
+        let setter_receiver = if access.mode.is_shared() {
+            Receiver::Ref
+        } else {
+            Receiver::Mut
+        };
+        // Setters return `self` for chaining when `with` methods are generated
+        let setter_type = (!config.skip_with).then(|| match setter_receiver {
+            Receiver::Mut => quote! { &mut Self },
+            _ => quote! { &Self },
+        });
+        let returned = setter_type.as_ref().map(|_| quote! { self });
+        let chained = if setter_type.is_some() {
+            " Returns `self` for chaining."
+        } else {
+            ""
+        };
+        if let Some(setter) = combined.setter(&self.name, inh, span)? {
+            if let FieldKind::Array(len) = self.kind {
+                let check = self.index_check(len);
+                let write = self.write_bit(storage, access, packed, &quote! { index });
+                let ty = &self.field.ty;
+                let array_setter = setter.with_suffix("_array");
+                accessors.push(Accessor {
+                    doc: format!("Sets element `index` of `{name}`, {location}. Panics if `index` is out of range.{chained}"),
+                    signature: setter,
+                    receiver: setter_receiver,
+                    is_const: false,
+                    params: quote! { index: usize, value: bool },
+                    ty: setter_type.clone(),
+                    body: quote! {
+                        #check
+                        #write
+                        #returned
+                    },
+                });
+                accessors.push(Accessor {
+                    doc: format!("Sets all elements of `{name}`, {location}.{chained}"),
+                    signature: array_setter,
+                    receiver: setter_receiver,
+                    is_const: false,
+                    params: quote! { values: #ty },
+                    ty: setter_type.clone(),
+                    body: quote! {
+                        for (index, value) in values.into_iter().enumerate() {
+                            #write
+                        }
+                        #returned
+                    },
+                });
+            } else {
+                let ty = self.value_type();
+                if access.mode.is_shared() {
+                    let swap = self.swap(storage, access, packed);
+                    if let FieldKind::Bool = self.kind {
+                        accessors.push(Accessor {
+                            doc: format!("Sets `{name}` to `true` and returns its previous value, {location}."),
+                            signature: setter.with_ident(format_ident!("fetch_set_{}", self.name.trim_start_matches("r#"))),
+                            receiver: Receiver::Ref,
+                            is_const: false,
+                            params: TokenStream::new(),
+                            ty: Some(self.getter_type()),
+                            body: quote! {
+                                let value = true;
+                                #swap
+                            },
+                        });
+                    }
+                    accessors.push(Accessor {
+                        doc: format!("Sets `{name}` and returns its previous value, {location}."),
+                        signature: setter.with_ident(format_ident!(
+                            "swap_{}",
+                            self.name.trim_start_matches("r#")
+                        )),
+                        receiver: Receiver::Ref,
+                        is_const: false,
+                        params: quote! { value: #ty },
+                        ty: Some(self.getter_type()),
+                        body: swap,
+                    });
+                }
+                let write = self.write(storage, access, packed);
+                accessors.push(Accessor {
+                    doc: format!("Sets `{name}`, {location}.{chained}"),
+                    signature: setter,
+                    receiver: setter_receiver,
+                    is_const: false,
+                    params: quote! { value: #ty },
+                    ty: setter_type,
+                    body: quote! {
+                        #write
+                        #returned
+                    },
+                });
+            }
+        }
+
+        if let Some(with) = combined.with(&self.name, inh, span)? {
+            // Enums are converted through traits and arrays are written in a loop, neither of which is
+            // possible in a `const fn`, and neither is formatting the panic message of a range check
+            let is_const = access.mode == StorageMode::Plain
+                && match self.kind {
+                    FieldKind::Bool | FieldKind::TriState => true,
+                    FieldKind::Int(_) => self.max_value().is_none(),
+                    FieldKind::Enum(_) | FieldKind::Array(_) => false,
+                };
+            let ty = self.value_type();
+            let assign = self.assign(storage, access, packed);
+            accessors.push(Accessor {
+                doc: format!("Returns `self` with `{name}` set, {location}."),
+                signature: with,
+                receiver: Receiver::Value,
+                is_const,
+                params: quote! { value: #ty },
+                ty: Some(quote! { Self }),
+                body: quote! {
+                    #assign
+                    self
+                },
+            });
+        }
+        Ok(accessors)
+    }
+
+    /// Describes where this field is stored, for the docs of its accessors
+    fn location(&self) -> String {
+        let bits = match self.kind.bit_width() {
+            1 => format!("bit {}", self.offset),
+            width => format!("bits {} to {}", self.offset, self.offset + width - 1),
+        };
+        let order = match self.order {
+            BitOrder::Lsb0 => "least",
+            BitOrder::Msb0 => "most",
+        };
+        format!("stored in {bits} of the bitflags, counting from the {order} significant bit")
+    }
+
+    /// The name of this field outside of Rust code, such as in `Debug` output: the name of the field, or
+    /// the name used in accessors for tuple fields
+    pub fn external_name(&self) -> String {
+        match &self.field.ident {
+            Some(ident) => ident.unraw().to_string(),
+            None => self.name.clone(),
+        }
+    }
+
+    /// The name of this field in `SCREAMING_SNAKE_CASE`, prefixed for tuple fields
+    pub fn constant_name(&self) -> String {
+        let name = self.name.trim_start_matches("r#").to_uppercase();
+        if name.starts_with(|c: char| c.is_ascii_digit()) {
+            format!("FIELD_{name}")
+        } else {
+            name
+        }
+    }
+
+    /// An associated constant holding the first bit of this field, numbered like in `#[pack_bools(bit = ..)]`
+    pub fn bit_constant(&self, inh: &Visibility) -> Option<TokenStream> {
+        if self.local.reserved {
+            return None;
+        }
+        let ident = format_ident!("{}_BIT", self.constant_name());
+        let doc = format!("The first bit of `{}`, {}", self.name, self.location());
+        let offset = self.offset;
+        Some(quote! {
+            #[doc = #doc]
+            #[allow(dead_code)]
+            #inh const #ident: usize = #offset;
+        })
+    }
+
+    /// The variant of the flag enum for this field, if it is a bool, accessing the bitflags at `packed`
+    pub fn flag(
+        &self,
+        storage: Storage,
+        access: Access,
+        packed: &TokenStream,
+    ) -> syn::Result<Option<Flag>> {
+        if !matches!(self.kind, FieldKind::Bool) || self.local.reserved {
+            return Ok(None);
+        }
+        let name = self.name.trim_start_matches("r#");
+        let mut variant: String = name
+            .split('_')
+            .filter(|part| !part.is_empty())
+            .map(|part| {
+                let mut chars = part.chars();
+                chars
+                    .next()
+                    .into_iter()
+                    .flat_map(char::to_uppercase)
+                    .chain(chars)
+                    .collect::<String>()
+            })
+            .collect();
+        if variant.starts_with(|c: char| c.is_ascii_digit()) {
+            variant.insert_str(0, "Field");
+        }
+        // Keywords such as `Self` can't be variants, and `ALL` is taken by the constant of the flag enum
+        let variant = match parse_str::<Ident>(&variant) {
+            Ok(variant) if variant != "ALL" => variant,
+            _ => {
+                let reason = if variant.is_empty() {
+                    "an empty name".to_string()
+                } else {
+                    format!("the invalid name `{variant}`")
+                };
+                return Err(Error::new(
+                    self.field.span(),
+                    format!(
+                        "the flag variant of `{}` would get {reason}, rename the field with \
+                        #[pack_bools(name = ..)]",
+                        self.name
+                    ),
+                ));
+            }
+        };
+        let (place, offset) = self.locate(storage, packed);
+        let toggle = access.store(&place, |word| quote! { #word ^= 1 << #offset; });
+        Ok(Some(Flag {
+            variant,
+            doc: format!("The flag `{}`, {}", self.name, self.location()),
+            read: self.read(storage, access, packed),
+            write: self.write(storage, access, packed),
+            toggle,
+        }))
+    }
+
+    /// A statement panicking if `index` is out of range for an array of length `len`
+    fn index_check(&self, len: usize) -> TokenStream {
+        let msg = format!(
+            "index out of bounds: `{}` has a length of {len} but the index is {{}}",
+            self.name
+        );
+        quote! {
+            ::core::assert!(index < #len, #msg, index);
+        }
+    }
+
+    /// An expression reading the bit at `index` within this field
+    fn read_bit(
+        &self,
+        storage: Storage,
+        access: Access,
+        packed: &TokenStream,
+        index: &TokenStream,
+    ) -> TokenStream {
+        let (packed, offset) = locate_index(storage, self.order, packed, self.offset, index);
+        access.load(&packed, |word| {
+            quote! {
+                #word & 1 << #offset != 0
+            }
+        })
+    }
+
+    /// Statements writing the bool `value` to the bit at `index` within this field
+    fn write_bit(
+        &self,
+        storage: Storage,
+        access: Access,
+        packed: &TokenStream,
+        index: &TokenStream,
+    ) -> TokenStream {
+        let (packed, offset) = locate_index(storage, self.order, packed, self.offset, index);
+        access.store(&packed, |word| {
+            quote! {
+                if value {
+                    #word |= 1 << #offset;
+                } else {
+                    #word &= !(1 << #offset);
+                }
+            }
+        })
+    }
+
+    /// The type returned by the getter of this field
+    fn getter_type(&self) -> TokenStream {
+        let ty = &self.field.ty;
+        match self.kind {
+            FieldKind::Enum(bits) => {
+                let repr = enum_repr(bits);
+                quote! {
+                    ::core::result::Result<#ty, <#ty as ::core::convert::TryFrom<#repr>>::Error>
+                }
+            }
+            _ => self.value_type(),
+        }
+    }
+
+    /// The type taken by the setter of this field
+    pub fn value_type(&self) -> TokenStream {
+        match self.kind {
+            FieldKind::Bool => quote! { bool },
+            FieldKind::TriState => quote! { ::core::option::Option<bool> },
+            FieldKind::Int(_) | FieldKind::Enum(_) | FieldKind::Array(_) => {
+                self.field.ty.to_token_stream()
+            }
+        }
+    }
+
+    /// An expression of the value of this field in the bitflags at `packed`, of the type returned by its
+    /// getter
+    pub fn value(&self, storage: Storage, access: Access, packed: &TokenStream) -> TokenStream {
+        if let FieldKind::Array(len) = self.kind {
+            let read = self.read_bit(storage, access, packed, &quote! { index });
+            quote! {
+                ::core::array::from_fn::<bool, #len, _>(|index| #read)
+            }
+        } else {
+            self.read(storage, access, packed)
+        }
+    }
+
+    /// Statements writing `value`, of the type taken by the setter of this field, to the bitflags at
+    /// `packed`
+    pub fn assign(&self, storage: Storage, access: Access, packed: &TokenStream) -> TokenStream {
+        if let FieldKind::Array(_) = self.kind {
+            let write = self.write_bit(storage, access, packed, &quote! { index });
+            quote! {
+                for (index, value) in value.into_iter().enumerate() {
+                    #write
+                }
+            }
+        } else {
+            self.write(storage, access, packed)
+        }
+    }
+
+    /// The largest value fitting in this field, if it is an integer narrower than its type
+    pub fn max_value(&self) -> Option<Literal> {
+        match self.kind {
+            FieldKind::Int(bits) if Some(bits) != uint_bit_width(&self.field.ty) => {
+                Some(mask(bits))
+            }
+            _ => None,
+        }
+    }
+
+    /// An expression reading the value of this field from the bitflags at `packed`
+    fn read(&self, storage: Storage, access: Access, packed: &TokenStream) -> TokenStream {
+        let (packed, offset) = self.locate(storage, packed);
+        access.load(&packed, |word| self.read_word(word, offset))
+    }
+
+    /// An expression reading the value of this field from `word`, the word containing it, where its least
+    /// significant bit is at `offset`
+    fn read_word(&self, packed: &TokenStream, offset: usize) -> TokenStream {
+        let ty = &self.field.ty;
+        match self.kind {
+            FieldKind::Bool => quote! {
+                #packed & 1 << #offset != 0
+            },
+            FieldKind::TriState => {
+                let value_offset = offset + 1;
+                quote! {
+                    if #packed & 1 << #offset != 0 {
+                        ::core::option::Option::Some(#packed & 1 << #value_offset != 0)
+                    } else {
+                        ::core::option::Option::None
+                    }
+                }
+            }
+            FieldKind::Int(bits) => {
+                let mask = mask(bits);
+                quote! {
+                    ((#packed >> #offset) & #mask) as #ty
+                }
+            }
+            FieldKind::Enum(bits) => {
+                let mask = mask(bits);
+                let repr = enum_repr(bits);
+                let try_from = quote_spanned! {ty.span()=>
+                    <#ty as ::core::convert::TryFrom<#repr>>::try_from
+                };
+                quote! {
+                    #try_from(((#packed >> #offset) & #mask) as #repr)
+                }
+            }
+            FieldKind::Array(_) => unreachable!("arrays are read one bit at a time"),
+        }
+    }
+
+    /// Statements writing `value` to this field in the bitflags at `packed`
+    fn write(&self, storage: Storage, access: Access, packed: &TokenStream) -> TokenStream {
+        let (packed, offset) = self.locate(storage, packed);
+        let prepare = self.prepare();
+        let write = access.store(&packed, |word| self.write_word(storage, word, offset));
+        quote! {
+            #prepare
+            #write
+        }
+    }
+
+    /// Statements writing `value` to this field in the bitflags at `packed`, evaluating to the previous
+    /// value of the field
+    fn swap(&self, storage: Storage, access: Access, packed: &TokenStream) -> TokenStream {
+        let (packed, offset) = self.locate(storage, packed);
+        let prepare = self.prepare();
+        let swap = access.swap(&packed, |word| self.write_word(storage, word, offset));
+        let read = self.read_word(&quote! { previous }, offset);
+        quote! {
+            #prepare
+            let previous = #swap;
+            #read
+        }
+    }
+
+    /// Statements converting `value` to an unsigned integer if needed, and checking that it fits in this
+    /// field
+    fn prepare(&self) -> TokenStream {
+        let ty = &self.field.ty;
+        let (convert, bits, width) = match self.kind {
+            FieldKind::Int(bits) => (None, bits, uint_bit_width(ty)),
+            FieldKind::Enum(bits) => {
+                let repr = enum_repr(bits);
+                let convert = quote_spanned! {ty.span()=>
+                    let value = <#repr as ::core::convert::From<#ty>>::from(value);
+                };
+                (Some(convert), bits, Some(repr.bit_width()))
+            }
+            _ => return TokenStream::new(),
+        };
+        let mask = mask(bits);
+        let check = (Some(bits) != width).then(|| {
+            let msg = format!("{{}} doesn't fit in the {bits} bits of `{}`", self.name);
+            quote! {
+                ::core::debug_assert!(value <= #mask, #msg, value);
+            }
+        });
+        quote! {
+            #convert
+            #check
+        }
+    }
+
+    /// Statements writing the prepared `value` to the place `packed` holding the word containing this
+    /// field, where its least significant bit is at `offset`
+    fn write_word(&self, storage: Storage, packed: &TokenStream, offset: usize) -> TokenStream {
+        match self.kind {
+            FieldKind::Bool => quote! {
+                if value {
+                    #packed |= 1 << #offset;
+                } else {
+                    #packed &= !(1 << #offset);
+                }
+            },
+            FieldKind::TriState => quote! {
+                let bits = match value {
+                    ::core::option::Option::None => 0b00,
+                    ::core::option::Option::Some(false) => 0b01,
+                    ::core::option::Option::Some(true) => 0b11,
+                };
+                #packed = #packed & !(0b11 << #offset) | bits << #offset;
+            },
+            FieldKind::Int(bits) | FieldKind::Enum(bits) => {
+                let mask = mask(bits);
+                let word = storage.word();
+                quote! {
+                    #packed = #packed & !(#mask << #offset) | ((value as #word) & #mask) << #offset;
+                }
+            }
+            FieldKind::Array(_) => unreachable!("arrays are written one bit at a time"),
+        }
+    }
+}
+
+impl PackedField {
+    /// The indices of the bits set in the bitflags when this field has its default value, counting from
+    /// the least significant bit of the first word
+    pub fn default_bits(&self, storage: Storage) -> Vec<usize> {
+        let physical = |offset, width| self.physical_bit(storage, offset, width);
+        match (self.kind, self.local.default) {
+            (FieldKind::Bool, DefaultValue::Bool(true)) => vec![physical(self.offset, 1)],
+            (FieldKind::TriState, DefaultValue::Bool(false)) => vec![physical(self.offset, 2)],
+            (FieldKind::TriState, DefaultValue::Bool(true)) => {
+                let bit = physical(self.offset, 2);
+                vec![bit, bit + 1]
+            }
+            (FieldKind::Array(len), DefaultValue::Bool(true)) => (0..len)
+                .map(|index| physical(self.offset + index, 1))
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    /// The indices of all bits of this field, counting from the least significant bit of the first word
+    pub fn physical_bits(&self, storage: Storage) -> Vec<usize> {
+        match self.kind {
+            FieldKind::Array(len) => (0..len)
+                .map(|index| self.physical_bit(storage, self.offset + index, 1))
+                .collect(),
+            kind => {
+                let bit = self.physical_bit(storage, self.offset, kind.bit_width());
+                (bit..bit + kind.bit_width()).collect()
+            }
+        }
+    }
+
+    /// The index of the least significant of the `width` bits starting at bit `offset`, counting from the
+    /// least significant bit of the first word
+    fn physical_bit(&self, storage: Storage, offset: usize, width: usize) -> usize {
+        let word_width = storage.word().bit_width() as usize;
+        let (word, bit) = physical(storage, self.order, offset, width);
+        word.unwrap_or(0) * word_width + bit
+    }
+
+    /// The word of the bitflags at `packed` containing this field, and the index of the least significant
+    /// bit of this field within that word
+    fn locate(&self, storage: Storage, packed: &TokenStream) -> (TokenStream, usize) {
+        let (word, bit) = physical(storage, self.order, self.offset, self.kind.bit_width());
+        let packed = match word {
+            Some(word) => quote! { #packed[#word] },
+            None => packed.clone(),
+        };
+        (packed, bit)
+    }
+}
+
+/// The index of the word containing the `width` bits starting at bit `offset` if the bitflags are
+/// stored in an array, and the index of the least significant of these bits within the word
+fn physical(
+    storage: Storage,
+    order: BitOrder,
+    offset: usize,
+    width: usize,
+) -> (Option<usize>, usize) {
+    let word_width = storage.word().bit_width() as usize;
+    let (word, offset) = match storage {
+        Storage::Int(_) => (None, offset),
+        Storage::Words(..) => (Some(offset / word_width), offset % word_width),
+    };
+    match order {
+        BitOrder::Lsb0 => (word, offset),
+        BitOrder::Msb0 => (word, word_width - offset - width),
+    }
+}
+
+/// The word of the bitflags at `packed` containing the bit `offset + index`, where `index` is only known
+/// at runtime, and the index of that bit within the word
+fn locate_index(
+    storage: Storage,
+    order: BitOrder,
+    packed: &TokenStream,
+    offset: usize,
+    index: &TokenStream,
+) -> (TokenStream, TokenStream) {
+    let word_width = storage.word().bit_width() as usize;
+    let bit = quote! { (#offset + #index) };
+    let (packed, bit) = match storage {
+        Storage::Int(_) => (packed.clone(), bit),
+        Storage::Words(..) => (
+            quote! { #packed[#bit / #word_width] },
+            quote! { (#bit % #word_width) },
+        ),
+    };
+    match order {
+        BitOrder::Lsb0 => (packed, bit),
+        BitOrder::Msb0 => {
+            let last = word_width - 1;
+            (packed, quote! { (#last - #bit) })
+        }
+    }
+}
+
+/// The unsigned integer type an enum stored in `bits` bits is converted through
+fn enum_repr(bits: u8) -> PackedType {
+    PackedType::smallest_larger_than(bits as usize).unwrap()
+}
+
+/// A mask with the lowest `bits` bits set
+fn mask(bits: u8) -> Literal {
+    let mask = u128::MAX >> (128 - bits as u32);
+    Literal::u128_unsuffixed(mask)
+}
+
+pub enum ModifiedField {
+    Excluded(Field),
+    PackedField(Field, FieldKind, Option<Box<LocalConfig>>),
+}
+
+impl ModifiedField {
+    pub fn from_field(field: Field) -> syn::Result<Self> {
+        let mut config: Option<LocalConfig> = None;
+        let Field {
+            attrs,
+            vis,
+            mutability,
+            ident,
+            colon_token,
+            ty,
+        } = field;
+
+        let mut config_span = None;
+        let mut new_attributes = Vec::with_capacity(attrs.len());
+        for attr in attrs {
+            if attr.path().is_ident("pack_bools") {
+                // Remove this attribute
+                if config.is_some() {
+                    return Err(Error::new(
+                        attr.span(),
+                        "At most one #[pack_bools] attribute allowed per field",
+                    ));
+                }
+                let span = attr.span();
+                match attr.meta {
+                    Meta::List(list) => {
+                        let pc = parse2(list.tokens)?;
+                        config = Some(pc);
+                        config_span = Some(span);
+                    }
+                    _ => {
+                        return Err(Error::new(
+                            span,
+                            "Use #[pack_bools(...)] for configuring fields",
+                        ))
+                    }
+                }
+            } else {
+                // Keep other attributes
+                new_attributes.push(attr);
+            }
+        }
+
+        let default_kind = FieldKind::detect(&ty);
+        let kind = match config_span {
+            Some(span)
+                if !config
+                    .as_ref()
+                    .is_some_and(|config| config.skip && default_kind.is_some()) =>
+            {
+                Some(FieldKind::from_field(&ty, config.as_ref(), span)?)
+            }
+            _ => None,
+        };
+        let field = Field {
+            attrs: new_attributes,
+            vis,
+            mutability,
+            ident,
+            colon_token,
+            ty,
+        };
+
+        let res = if let Some(config) = config {
+            if config.skip {
+                Self::Excluded(field)
+            } else {
+                Self::PackedField(field, kind.unwrap(), Some(Box::new(config)))
+            }
+        } else if let Some(kind) = default_kind {
+            Self::PackedField(field, kind, None)
+        } else {
+            Self::Excluded(field)
+        };
+        Ok(res)
+    }
+}
+
+/// The name used when substituting `%` in accessor templates: the local `name` if given, otherwise the
+/// field identifier for named fields or the field index for tuple fields
+fn field_name(field: &Field, local: &LocalConfig, idx: usize) -> String {
+    if let Some(name) = &local.name {
+        name.to_string()
+    } else if let Some(ident) = &field.ident {
+        ident.to_string()
+    } else {
+        idx.to_string()
+    }
+}
+
+fn is_bool_type(ty: &Type) -> bool {
+    if let Type::Path(p) = ty {
+        p.path.is_ident("bool")
+    } else {
+        false
+    }
+}
+
+/// Whether this is `Option<bool>`, possibly with a path to `Option`
+fn is_tristate_type(ty: &Type) -> bool {
+    let Type::Path(p) = ty else {
+        return false;
+    };
+    let Some(segment) = p.path.segments.last() else {
+        return false;
+    };
+    if segment.ident != "Option" {
+        return false;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return false;
+    };
+    matches!(
+        args.args.first(),
+        Some(GenericArgument::Type(ty)) if args.args.len() == 1 && is_bool_type(ty)
+    )
+}
+
+/// The length of a `[bool; N]` where `N` is a non-zero integer literal
+fn bool_array_len(ty: &Type) -> Option<usize> {
+    let Type::Array(array) = ty else {
+        return None;
+    };
+    if !is_bool_type(&array.elem) {
+        return None;
+    }
+    let Expr::Lit(ExprLit {
+        lit: Lit::Int(len), ..
+    }) = &array.len
+    else {
+        return None;
+    };
+    len.base10_parse().ok().filter(|&len| len > 0)
+}
+
+fn uint_bit_width(ty: &Type) -> Option<u8> {
+    let Type::Path(p) = ty else {
+        return None;
+    };
+    let width = match p.path.get_ident()?.to_string().as_str() {
+        "u8" => 8,
+        "u16" => 16,
+        "u32" => 32,
+        "u64" => 64,
+        "u128" => 128,
+        _ => return None,
+    };
+    Some(width)
+}
@@ -0,0 +1,240 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::Error;
+use syn::spanned::Spanned;
+
+use crate::pack_bools::config::{GlobalConfig, Storage};
+use crate::pack_bools::fields::{FieldKind, SplitFields};
+use crate::pack_bools::storage::Access;
+use crate::pack_bools::storage_value;
+
+/// The constants of the newtype besides the constants of the flags
+const MASK_CONSTANTS: &[&str] = &["ALL_BITS", "KNOWN_BITS"];
+
+/// Returns the constants, methods and operators making the newtype usable as a set of the packed bools,
+/// like the types generated by `bitflags`. Returns `None` if the newtype pattern isn't used or the
+/// bitflags can't be copied.
+pub fn flag_set(
+    config: &GlobalConfig,
+    newtype: &TokenStream,
+    split: &SplitFields,
+    storage: Storage,
+) -> syn::Result<Option<TokenStream>> {
+    if !config.gen_type.is_newtype() || config.storage_mode.is_shared() {
+        return Ok(None);
+    }
+    let access = Access::plain();
+    let flags: Vec<_> = split
+        .to_convert
+        .iter()
+        .filter(|packed| matches!(packed.kind, FieldKind::Bool) && !packed.local.reserved)
+        .collect();
+    if let Some(packed) = flags
+        .iter()
+        .find(|packed| MASK_CONSTANTS.contains(&packed.constant_name().as_str()))
+    {
+        return Err(Error::new(
+            packed.field.span(),
+            format!(
+                "the flag constant of `{}` would clash with `{}` of the newtype, rename the field with \
+                #[pack_bools(name = ..)]",
+                packed.name,
+                packed.constant_name()
+            ),
+        ));
+    }
+    let names: Vec<_> = flags
+        .iter()
+        .map(|packed| format_ident!("{}", packed.constant_name()))
+        .collect();
+    let docs = flags
+        .iter()
+        .map(|packed| format!("The flag of `{}`", packed.name));
+    let values = flags
+        .iter()
+        .map(|packed| storage_value(access, storage, &packed.physical_bits(storage)));
+    let all_bits: Vec<_> = flags
+        .iter()
+        .flat_map(|packed| packed.physical_bits(storage))
+        .collect();
+    let all = storage_value(access, storage, &all_bits);
+    let empty = storage_value(access, storage, &[]);
+    let len = flags.len();
+    let word_type = access.storage_type(storage);
+    let all_bits = storage_value(access, storage, &split.assigned_bits(storage));
+    let known_bits = storage_value(access, storage, &split.known_bits(storage));
+
+    // An expression combining the words of `self` and `other`
+    let and = match storage {
+        Storage::Int(_) => quote! { self.0 & other.0 },
+        Storage::Words(_, words) => quote! {
+            ::core::array::from_fn::<_, #words, _>(|index| self.0[index] & other.0[index])
+        },
+    };
+    // Whether `bits` has no bit set outside of `Self::KNOWN_BITS`, and the value of `bits` without them
+    let (is_known, truncate) = match storage {
+        Storage::Int(_) => (
+            quote! { bits & !Self::KNOWN_BITS == 0 },
+            quote! { bits & Self::KNOWN_BITS },
+        ),
+        Storage::Words(_, words) => (
+            quote! {
+                bits.iter().zip(Self::KNOWN_BITS).all(|(word, known)| word & !known == 0)
+            },
+            quote! {
+                ::core::array::from_fn::<_, #words, _>(|index| bits[index] & Self::KNOWN_BITS[index])
+            },
+        ),
+    };
+    // Statements updating the words of `self` with the words of `other`
+    let assign = |op: TokenStream, other: TokenStream| match storage {
+        Storage::Int(_) => quote! {
+            let other = other.0;
+            self.0 #op #other;
+        },
+        Storage::Words(..) => quote! {
+            for (word, other) in self.0.iter_mut().zip(other.0) {
+                *word #op #other;
+            }
+        },
+    };
+    let insert = assign(quote! { |= }, quote! { other });
+    let retain = assign(quote! { &= }, quote! { other });
+    let toggle = assign(quote! { ^= }, quote! { other });
+    let remove = assign(quote! { &= }, quote! { !other });
+
+    let operators = [
+        (quote! { BitOr }, quote! { bitor }, &insert),
+        (quote! { BitAnd }, quote! { bitand }, &retain),
+        (quote! { BitXor }, quote! { bitxor }, &toggle),
+        (quote! { Sub }, quote! { sub }, &remove),
+    ]
+    .into_iter()
+    .map(|(op, op_fn, assign)| {
+        let op_assign = format_ident!("{op}Assign");
+        let op_assign_fn = format_ident!("{op_fn}_assign");
+        quote! {
+            impl ::core::ops::#op for #newtype {
+                type Output = Self;
+
+                fn #op_fn(mut self, other: Self) -> Self {
+                    ::core::ops::#op_assign::#op_assign_fn(&mut self, other);
+                    self
+                }
+            }
+
+            impl ::core::ops::#op_assign for #newtype {
+                fn #op_assign_fn(&mut self, other: Self) {
+                    #assign
+                }
+            }
+        }
+    });
+
+    Ok(Some(quote! {
+        #[allow(dead_code)]
+        impl #newtype {
+            #(
+                #[doc = #docs]
+                pub const #names: Self = Self(#values);
+            )*
+
+            /// The mask of the bits assigned to packed fields, including fields other than bools
+            pub const ALL_BITS: #word_type = #all_bits;
+
+            /// The mask of the bits assigned to packed fields, including reserved fields and bits
+            pub const KNOWN_BITS: #word_type = #known_bits;
+
+            /// Returns the raw bits
+            pub const fn bits(&self) -> #word_type {
+                self.0
+            }
+
+            /// Converts raw bits, returning `None` if any bit outside of `KNOWN_BITS` is set
+            pub fn from_bits(bits: #word_type) -> ::core::option::Option<Self> {
+                if #is_known {
+                    ::core::option::Option::Some(Self(bits))
+                } else {
+                    ::core::option::Option::None
+                }
+            }
+
+            /// Converts raw bits, clearing the bits outside of `KNOWN_BITS`
+            pub fn from_bits_truncate(bits: #word_type) -> Self {
+                Self(#truncate)
+            }
+
+            /// Converts raw bits, keeping the bits outside of `KNOWN_BITS` as they are
+            pub const fn from_bits_retain(bits: #word_type) -> Self {
+                Self(bits)
+            }
+
+            /// Returns the set without any flags
+            pub const fn empty() -> Self {
+                Self(#empty)
+            }
+
+            /// Returns the set of all flags
+            pub const fn all() -> Self {
+                Self(#all)
+            }
+
+            /// Whether all flags in `other` are set
+            pub fn contains(&self, other: Self) -> bool {
+                (#and) == other.0
+            }
+
+            /// Whether any flag in `other` is set
+            pub fn intersects(&self, other: Self) -> bool {
+                (#and) != Self::empty().0
+            }
+
+            /// Sets the flags in `other`
+            pub fn insert(&mut self, other: Self) {
+                #insert
+            }
+
+            /// Clears the flags in `other`
+            pub fn remove(&mut self, other: Self) {
+                #remove
+            }
+
+            /// Inverts the flags in `other`
+            pub fn toggle(&mut self, other: Self) {
+                #toggle
+            }
+
+            /// Whether no flag is set. The packed fields other than bools are ignored.
+            pub fn is_empty(&self) -> bool {
+                !self.intersects(Self::all())
+            }
+
+            /// Whether all flags are set. The packed fields other than bools are ignored.
+            pub fn is_all(&self) -> bool {
+                self.contains(Self::all())
+            }
+
+            /// Returns an iterator over the flags that are set, as sets of a single flag
+            pub fn iter(&self) -> impl ::core::iter::Iterator<Item = Self> {
+                // The newtype isn't `Copy` if its derives are set explicitly, but its words are
+                let this = Self(self.0);
+                let flags: [Self; #len] = [#(Self::#names),*];
+                flags
+                    .into_iter()
+                    .filter(move |flag| this.contains(Self(flag.0)))
+            }
+        }
+
+        #(#operators)*
+
+        impl ::core::ops::Not for #newtype {
+            type Output = Self;
+
+            /// Inverts all flags, leaving the packed fields other than bools unchanged
+            fn not(mut self) -> Self {
+                self.toggle(Self::all());
+                self
+            }
+        }
+    }))
+}
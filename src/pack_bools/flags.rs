@@ -0,0 +1,110 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use syn::{Error, Generics, Visibility};
+use syn::spanned::Spanned;
+
+use crate::pack_bools::config::{GlobalConfig, Storage};
+use crate::pack_bools::fields::{Flag, SplitFields};
+use crate::pack_bools::storage::Access;
+
+/// Returns the definition of the flag enum, with a variant per packed bool, along with the methods
+/// accessing the bools by flag. Returns `None` if no flag enum should be generated, and an error if the
+/// names of the variants clash.
+pub fn flag_enum(
+    config: &GlobalConfig,
+    vis: &Visibility,
+    ident: &Ident,
+    generics: &Generics,
+    split: &SplitFields,
+    storage: Storage,
+    packed_path: &TokenStream,
+) -> syn::Result<Option<(TokenStream, TokenStream)>> {
+    let Some(name) = config.flag_enum.as_ref() else {
+        return Ok(None);
+    };
+    let flag_enum = name.clone().unwrap_or_else(|| format_ident!("{ident}Flag"));
+    let access = Access::new(config);
+    let packed = quote! { self.#packed_path };
+    let mut flags: Vec<Flag> = vec![];
+    for packed_field in &split.to_convert {
+        let Some(flag) = packed_field.flag(storage, access, &packed)? else {
+            continue;
+        };
+        if flags.iter().any(|other| other.variant == flag.variant) {
+            return Err(Error::new(
+                packed_field.field.span(),
+                format!(
+                    "the flag variant `{}` of `{}` is already taken by another field, rename the field \
+                    with #[pack_bools(name = ..)]",
+                    flag.variant, packed_field.name
+                ),
+            ));
+        }
+        flags.push(flag);
+    }
+
+    let variants: Vec<_> = flags.iter().map(|flag| &flag.variant).collect();
+    let docs = flags.iter().map(|flag| &flag.doc);
+    let reads = flags.iter().map(|flag| &flag.read);
+    let writes = flags.iter().map(|flag| &flag.write);
+    let toggles = flags.iter().map(|flag| &flag.toggle);
+    let len = flags.len();
+    let receiver = if access.mode.is_shared() {
+        quote! { &self }
+    } else {
+        quote! { &mut self }
+    };
+
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+    let doc = format!("The packed bools of [`{ident}`], see [`{ident}::get`]");
+    let definition = quote! {
+        #[doc = #doc]
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+        #vis enum #flag_enum {
+            #(
+                #[doc = #docs]
+                #variants,
+            )*
+        }
+
+        impl #flag_enum {
+            /// All flags, in the order of their fields
+            #vis const ALL: [#flag_enum; #len] = [#(#flag_enum::#variants),*];
+        }
+
+        impl #impl_generics ::core::ops::Index<#flag_enum> for #ident #type_generics #where_clause {
+            type Output = bool;
+
+            fn index(&self, flag: #flag_enum) -> &bool {
+                if self.get(flag) {
+                    &true
+                } else {
+                    &false
+                }
+            }
+        }
+    };
+    let methods = quote! {
+        /// Returns the packed bool corresponding to `flag`
+        #vis fn get(&self, flag: #flag_enum) -> bool {
+            match flag {
+                #(#flag_enum::#variants => #reads,)*
+            }
+        }
+
+        /// Sets the packed bool corresponding to `flag` to `value`
+        #vis fn set(#receiver, flag: #flag_enum, value: bool) {
+            match flag {
+                #(#flag_enum::#variants => { #writes })*
+            }
+        }
+
+        /// Inverts the packed bool corresponding to `flag`
+        #vis fn toggle(#receiver, flag: #flag_enum) {
+            match flag {
+                #(#flag_enum::#variants => { #toggles })*
+            }
+        }
+    };
+    Ok(Some((definition, methods)))
+}
@@ -1,13 +1,12 @@
-use std::iter;
-
-use proc_macro2::TokenStream;
+use proc_macro2::{Ident, Literal, TokenStream};
 use quote::{format_ident, quote, ToTokens};
-use syn::{Error, Field, ItemStruct, Meta, parse2, Type};
+use syn::{Attribute, Error, Fields, Item, ItemStruct, parse_quote, Path, Token, Visibility};
+use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 
-use crate::pack_bools::config::{CombinedConfig, GenType, GlobalConfig, LocalConfig};
-
-pub mod config;
+use crate::pack_bools::config::{DebugImpl, GenType, GlobalConfig, PackedType, Storage, StorageMode};
+use crate::pack_bools::fields::{Accessor, FieldKind, SplitFields};
+use crate::pack_bools::storage::Access;
 
 macro_rules! try_syn {
     ($e:expr) => {
@@ -18,10 +17,79 @@ macro_rules! try_syn {
     };
 }
 
-#[allow(clippy::too_many_lines)]
-pub fn pack_bools(config: GlobalConfig, definition: ItemStruct) -> TokenStream {
+pub mod config;
+mod debug;
+mod enums;
+mod fields;
+mod flag_set;
+mod flags;
+mod serde;
+mod storage;
+mod unpacked;
+
+pub fn pack_bools(config: GlobalConfig, item: Item) -> TokenStream {
+    if let Some(ordering) = &config.ordering {
+        if config.storage_mode != StorageMode::Atomic {
+            return Error::new(
+                ordering.span(),
+                "#[pack_bools(ordering = ..)] only applies to #[pack_bools(storage = atomic)]",
+            )
+            .to_compile_error();
+        }
+    }
+
+    if let (Some(helper), None) = (config.unpacked_helpers.first(), &config.unpacked_derives) {
+        return Error::new(
+            helper.span(),
+            "#[pack_bools(unpacked_helpers(..))] only applies to #[pack_bools(derive_unpacked(..))]",
+        )
+        .to_compile_error();
+    }
+
+    match item {
+        Item::Struct(item_struct) => pack_struct(config, item_struct),
+        Item::Enum(item_enum) => {
+            if let Some(option) = struct_only_option(&config) {
+                return Error::new(
+                    item_enum.ident.span(),
+                    format!("#[pack_bools({option})] can only be used on structs"),
+                )
+                .to_compile_error();
+            }
+            enums::pack_enum(config, item_enum)
+        }
+        item => Error::new(
+            item.span(),
+            "#[pack_bools] can only be used on structs and enums",
+        )
+        .to_compile_error(),
+    }
+}
+
+/// Returns the first option given that only applies to structs, if any
+fn struct_only_option(config: &GlobalConfig) -> Option<&'static str> {
+    let options = [
+        ("flags", config.flag_enum.is_some()),
+        ("debug", config.debug.is_some()),
+        ("serde", config.serde.is_some()),
+        ("unpacked", config.unpacked.is_some()),
+        ("derive_unpacked", config.unpacked_derives.is_some()),
+        ("unpacked_helpers", !config.unpacked_helpers.is_empty()),
+        ("new", config.constructor.is_some()),
+        ("raw_bits", config.raw_bits.is_some()),
+        ("with", !config.skip_with),
+    ];
+    options.into_iter().find_map(|(option, used)| used.then_some(option))
+}
+
+fn pack_struct(config: GlobalConfig, definition: ItemStruct) -> TokenStream {
+    if let Fields::Unit = definition.fields {
+        // There is nothing to pack in a unit struct
+        return definition.into_token_stream();
+    }
+
     let ItemStruct {
-        attrs,
+        mut attrs,
         vis,
         struct_token,
         ident,
@@ -29,226 +97,493 @@ pub fn pack_bools(config: GlobalConfig, definition: ItemStruct) -> TokenStream {
         fields,
         semi_token: _,
     } = definition;
-    let modified: Result<Vec<_>, _> = fields.into_iter().map(ModifiedField::from_field).collect();
-    let modified = try_syn!(modified);
-
-    let (to_convert, to_keep): (Vec<_>, Vec<_>) =
-        modified.into_iter().partition(ModifiedField::is_packed);
+    if config.debug == Some(DebugImpl::NewType) && config.gen_type.is_inline() {
+        return Error::new(
+            ident.span(),
+            "#[pack_bools(debug)] requires #[pack_bools(newtype)], use #[pack_bools(debug = struct)] with inline bitflags",
+        )
+        .to_compile_error();
+    }
+    let mut split = try_syn!(SplitFields::new(fields, &config, ident.span()));
+    if let Some(width) = config.packed_type.fixed_bit_width() {
+        try_syn!(split.check_pinned(width));
+    }
 
-    let to_keep: Option<Vec<_>> = to_keep
-        .into_iter()
-        .map(ModifiedField::into_excluded)
-        .collect();
-
-    let to_keep = to_keep.unwrap();
-    let new_field: TokenStream = config.field_name.0.parse().unwrap();
-    let inner_type = try_syn!(config
-        .packed_type
-        .to_packed_type(to_convert.len(), ident.span()));
-    let (packed_type, newtype) = match &config.gen_type {
-        GenType::Inline => (inner_type.to_token_stream(), None),
-        GenType::NewType(name) => {
-            let newtype_name = if let Some(name) = name {
-                name.parse().unwrap()
-            } else {
-                format_ident!("{ident}PackedBools").to_token_stream()
-            };
-            let typedef = quote! {
-                #[derive(Copy, Clone, Debug)]
-                #[repr(transparent)]
-                struct #newtype_name (#inner_type);
-            };
-            (newtype_name, Some(typedef))
-        }
-    };
+    let inner_type = try_syn!(config.packed_type.to_storage(
+        config.storage_mode,
+        split.bits,
+        ident.span(),
+        |word| split.align(word)
+    ));
+    let (packed_type, newtype) = try_syn!(packed_type(&config, &attrs, &ident, inner_type));
 
+    let member = &split.member;
     let packed_path = if config.gen_type.is_inline() {
-        new_field.clone()
+        member.to_token_stream()
     } else {
-        quote! { #new_field.0 }
+        quote! { #member.0 }
     };
 
-    let mut default_bits = 0u128;
+    let mut default_bits = vec![];
+    let raw_names = raw_bits_names(&config);
     let mut functions = vec![];
-    for (idx, (field, local)) in to_convert
-        .into_iter()
-        .map(|f| {
-            let (a, b) = f.into_packaged().unwrap();
-            (a, b.unwrap_or_default())
-        })
-        .enumerate()
-    {
-        if local.default {
+    for packed in &split.to_convert {
+        let field_default = packed.default_bits(inner_type);
+        if !field_default.is_empty() {
             if config.gen_type.is_inline() {
-                return Error::new(field.span(), "#[pack_bools(default = true)] only available on `newtype` generation: use #[pack_bools(newtype)] on the struct").to_compile_error();
+                let msg = if let FieldKind::Bool = packed.kind {
+                    "#[pack_bools(default = true)] only available on `newtype` generation: use #[pack_bools(newtype)] on the struct"
+                } else {
+                    "#[pack_bools(default = true/false)] on Option<bool> only available on `newtype` generation: use #[pack_bools(newtype)] on the struct"
+                };
+                return Error::new(packed.field.span(), msg).to_compile_error();
             }
-            default_bits |= 1 << idx;
-        }
-
-        let idx = idx.to_token_stream();
-        let combined = CombinedConfig::new(&config, &local);
-        if let Some(getter) = combined.getter(
-            field.ident.as_ref().unwrap().to_string().as_str(),
-            &field.vis,
-        ) {
-            let getter = quote! {
-                #getter (&self) -> bool {
-                    self.#packed_path & 1 << #idx != 0
-                }
-            };
-            functions.push(getter);
-        }
-
-        if let Some(setter) = combined.setter(
-            field.ident.as_ref().unwrap().to_string().as_str(),
-            &field.vis,
-        ) {
-            let setter = quote! {
-                #setter (&mut self, value: bool) {
-                    if value {
-                        self.#packed_path |= 1 << #idx;
-                    } else {
-                        self.#packed_path &= !(1 << #idx);
-                    }
-                }
-            };
-            functions.push(setter);
+            default_bits.extend(field_default);
         }
-    }
 
-    let default_impl = config.gen_type.is_newtype().then(|| {
-        quote! {
-            impl ::std::default::Default for #packed_type {
-                fn default() -> Self {
-                    Self( #default_bits as #inner_type )
-                }
+        let accessors = try_syn!(packed.accessors(
+            &config,
+            &packed.field.vis,
+            &quote! { self.#packed_path },
+            inner_type,
+            Access::new(&config)
+        ));
+        if let Some((getter, setter)) = &raw_names {
+            let clash = accessors
+                .iter()
+                .map(|accessor| &accessor.signature.ident)
+                .find(|ident| *ident == getter || *ident == setter);
+            if let Some(clash) = clash {
+                let msg = format!(
+                    "the accessor `{clash}` of `{}` clashes with the raw bits accessor of the same name, \
+                    rename the field with #[pack_bools(name = ..)] or the raw bits accessors with \
+                    #[pack_bools(raw_bits = ..)]",
+                    packed.name
+                );
+                return Error::new(packed.field.span(), msg).to_compile_error();
             }
         }
-    });
+        functions.extend(packed.bit_constant(&packed.field.vis));
+        functions.extend(accessors.into_iter().map(Accessor::into_method));
+    }
+
+    functions.extend(raw_bits(&config, &vis, &split, inner_type, &packed_path));
+    functions.extend(unpacked::constructor(
+        &config,
+        &vis,
+        &ident,
+        &split,
+        inner_type,
+        &packed_type,
+    ));
+
+    let default_impl = default_impl(&config, &packed_type, inner_type, &default_bits);
+    let flag_set = try_syn!(flag_set::flag_set(&config, &packed_type, &split, inner_type));
+    let newtype_debug = debug::newtype_debug(&config, &packed_type, &split, inner_type);
+    let unpacked = unpacked::unpacked_struct(&config, &attrs, &vis, &ident, &generics, &split);
+    let (unpacked, conversions) = unpacked
+        .map(|(unpacked, definition)| {
+            let conversions = unpacked::conversions(
+                &config,
+                &ident,
+                &generics,
+                &unpacked,
+                &split,
+                inner_type,
+                &packed_type,
+            );
+            (definition, conversions)
+        })
+        .unzip();
+    try_syn!(unpacked::strip_helper_attributes(
+        &config, &mut attrs, &mut split
+    ));
+    let struct_debug = try_syn!(debug::struct_debug(
+        &config,
+        &mut attrs,
+        &ident,
+        &generics,
+        &split,
+        inner_type,
+        &packed_path
+    ));
+    let snapshot = match (config.storage_mode, inner_type) {
+        (StorageMode::Atomic | StorageMode::Volatile, Storage::Int(word)) => Some(try_syn!(
+            snapshot(&config, &vis, &ident, &split, word, &packed_path)
+        )),
+        _ => None,
+    };
+    let (snapshot, snapshot_functions) = snapshot.unzip();
+    let (flag_enum, flag_functions) = try_syn!(flags::flag_enum(
+        &config,
+        &vis,
+        &ident,
+        &generics,
+        &split,
+        inner_type,
+        &packed_path,
+    ))
+    .unzip();
+    let (serde_attrs, serde_impls, serde_functions) = try_syn!(serde::serde_impls(
+        &config,
+        &attrs,
+        &ident,
+        &generics,
+        &packed_type,
+        &split,
+        inner_type
+    ));
 
     let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
 
-    let fields = to_keep
-        .into_iter()
-        .map(Field::into_token_stream)
-        .chain(iter::once(quote! { #new_field: #packed_type }));
+    let is_tuple = split.is_tuple;
+    let body = split.into_fields(Some(&packed_type), &serde_attrs);
+    let body = if is_tuple {
+        quote! { #body #where_clause; }
+    } else {
+        quote! { #where_clause #body }
+    };
 
     let output = quote! {
-        #(#attrs)* #vis #struct_token #ident #generics {
-            #(#fields),*
-        }
+        #(#attrs)* #vis #struct_token #ident #generics #body
 
         #newtype
 
         #default_impl
 
+        #flag_set
+
+        #newtype_debug
+
+        #struct_debug
+
+        #snapshot
+
+        #flag_enum
+
+        #serde_impls
+
+        #unpacked
+
+        #conversions
+
         impl #impl_generics #ident #type_generics #where_clause {
             #(#functions)*
+            #snapshot_functions
+            #flag_functions
+            #serde_functions
         }
     };
 
     output
 }
 
-pub enum ModifiedField {
-    Excluded(Field),
-    PackedField(Field, Option<LocalConfig>),
+/// Returns the type of the field containing the bitflags, along with the definition of the newtype if
+/// the `newtype` pattern is used
+fn packed_type(
+    config: &GlobalConfig,
+    attrs: &[Attribute],
+    ident: &Ident,
+    inner_type: Storage,
+) -> syn::Result<(TokenStream, Option<TokenStream>)> {
+    let storage_type = Access::new(config).storage_type(inner_type);
+    match &config.gen_type {
+        GenType::Inline => Ok((storage_type, None)),
+        GenType::NewType(name) => {
+            let newtype_name = if let Some(name) = name {
+                name.parse().unwrap()
+            } else {
+                format_ident!("{ident}PackedBools").to_token_stream()
+            };
+            let derives = newtype_derives(config, attrs)?;
+            let typedef = quote! {
+                #[derive(#(#derives),*)]
+                #[repr(transparent)]
+                struct #newtype_name (#storage_type);
+            };
+            Ok((newtype_name, Some(typedef)))
+        }
+    }
 }
 
-impl ModifiedField {
-    pub fn is_packed(&self) -> bool {
-        matches!(self, ModifiedField::PackedField(_, _))
+/// Returns the derives of the newtype: the ones given with `newtype_derive` if any, otherwise the ones
+/// supported by the storage, along with the comparison and hashing derives of the struct that the
+/// storage supports
+fn newtype_derives(config: &GlobalConfig, attrs: &[Attribute]) -> syn::Result<Vec<Path>> {
+    let generated_debug = config.debug == Some(DebugImpl::NewType);
+    let is_debug = |path: &Path| {
+        path.segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Debug")
+    };
+    if let Some(derives) = &config.newtype_derives {
+        return Ok(derives
+            .iter()
+            .filter(|path| !(generated_debug && is_debug(path)))
+            .cloned()
+            .collect());
     }
 
-    pub fn into_excluded(self) -> Option<Field> {
-        match self {
-            ModifiedField::Excluded(e) => Some(e),
-            ModifiedField::PackedField(_, _) => None,
-        }
+    let (mut derives, mirrored): (Vec<Path>, &[&str]) = match config.storage_mode {
+        StorageMode::Plain | StorageMode::Volatile => (
+            vec![parse_quote! { Copy }, parse_quote! { Clone }],
+            &["PartialEq", "Eq", "PartialOrd", "Ord", "Hash"],
+        ),
+        // `Cell` is `Clone` but not `Copy`, and isn't `Hash`
+        StorageMode::Cell => (
+            vec![parse_quote! { Clone }],
+            &["PartialEq", "Eq", "PartialOrd", "Ord"],
+        ),
+        StorageMode::Atomic => (vec![], &[]),
+    };
+    if !generated_debug {
+        derives.push(parse_quote! { Debug });
     }
-
-    pub fn into_packaged(self) -> Option<(Field, Option<LocalConfig>)> {
-        match self {
-            ModifiedField::PackedField(f, c) => Some((f, c)),
-            ModifiedField::Excluded(_) => None,
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("derive")) {
+        let parent_derives =
+            attr.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)?;
+        for path in parent_derives {
+            let Some(segment) = path.segments.last() else {
+                continue;
+            };
+            if mirrored.iter().any(|name| segment.ident == name) {
+                let name = &segment.ident;
+                derives.push(parse_quote! { #name });
+            }
         }
     }
+    Ok(derives)
 }
 
-impl ModifiedField {
-    pub fn from_field(field: Field) -> syn::Result<Self> {
-        let mut config: Option<LocalConfig> = None;
-        let Field {
-            attrs,
-            vis,
-            mutability,
-            ident,
-            colon_token,
-            ty,
-        } = field;
-
-        let mut new_attributes = Vec::with_capacity(attrs.len());
-        for attr in attrs {
-            if attr.path().is_ident("pack_bools") {
-                // Remove this attribute
-                if !is_bool_type(&ty) {
-                    return Err(Error::new(
-                        attr.span(),
-                        "#[pack_bools] can only be used on bools",
-                    ));
-                } else if config.is_some() {
-                    return Err(Error::new(
-                        attr.span(),
-                        "At most one #[pack_bools] attribute allowed per field",
-                    ));
-                }
-                match attr.meta {
-                    Meta::List(list) => {
-                        let pc = parse2(list.tokens)?;
-                        config = Some(pc);
-                    }
-                    _ => {
-                        return Err(Error::new(
-                            attr.span(),
-                            "Use #[pack_bools(...)] for configuring fields",
-                        ))
-                    }
+/// Returns the `Default` impl of the newtype if the `newtype` pattern is used
+fn default_impl(
+    config: &GlobalConfig,
+    packed_type: &TokenStream,
+    inner_type: Storage,
+    default_bits: &[usize],
+) -> Option<TokenStream> {
+    let access = Access::new(config);
+    config.gen_type.is_newtype().then(|| {
+        let value = storage_value(access, inner_type, default_bits);
+        quote! {
+            impl ::core::default::Default for #packed_type {
+                fn default() -> Self {
+                    Self( #value )
                 }
-            } else {
-                // Keep other attributes
-                new_attributes.push(attr);
             }
         }
+    })
+}
 
-        let is_bool = is_bool_type(&ty);
-        let field = Field {
-            attrs: new_attributes,
-            vis,
-            mutability,
-            ident,
-            colon_token,
-            ty,
-        };
+/// The names of the methods reading and overwriting the raw bitflags, if requested
+fn raw_bits_names(config: &GlobalConfig) -> Option<(Ident, Ident)> {
+    let name = config.raw_bits.as_ref()?;
+    let name = name.clone().unwrap_or_else(|| format_ident!("packed_bits"));
+    let setter = format_ident!("set_{name}");
+    Some((name, setter))
+}
 
-        let res = if let Some(config) = config {
-            if config.skip {
-                Self::Excluded(field)
-            } else {
-                Self::PackedField(field, Some(config))
-            }
-        } else if is_bool {
-            Self::PackedField(field, None)
-        } else {
-            Self::Excluded(field)
-        };
-        Ok(res)
+/// Returns the masks of the bits assigned to packed fields, along with methods reading and overwriting
+/// the raw bitflags, if requested
+fn raw_bits(
+    config: &GlobalConfig,
+    vis: &Visibility,
+    split: &SplitFields,
+    storage: Storage,
+    packed_path: &TokenStream,
+) -> Option<TokenStream> {
+    let (getter, setter) = raw_bits_names(config)?;
+    let access = Access::new(config);
+    let word_type = Access::plain().storage_type(storage);
+    let all_bits = storage_value(Access::plain(), storage, &split.assigned_bits(storage));
+    let known_bits = storage_value(Access::plain(), storage, &split.known_bits(storage));
+    let place = quote! { self.#packed_path };
+    let (get, set) = match storage {
+        Storage::Words(_, words) if access.mode != StorageMode::Plain => {
+            let places = (0..words).map(|word| quote! { #place[#word] });
+            let gets = places.clone().map(|place| access.get(&place));
+            let sets = places
+                .zip(0..words)
+                .map(|(place, word)| access.set(&place, &quote! { bits[#word] }));
+            (quote! { [#(#gets),*] }, quote! { #(#sets)* })
+        }
+        _ => (access.get(&place), access.set(&place, &quote! { bits })),
+    };
+    let receiver = if access.mode.is_shared() {
+        quote! { &self }
+    } else {
+        quote! { &mut self }
+    };
+    Some(quote! {
+        /// The mask of the bits of the bitflags assigned to packed fields
+        #[allow(dead_code)]
+        #vis const ALL_BITS: #word_type = #all_bits;
+
+        /// The mask of the bits of the bitflags assigned to packed fields, including reserved fields and bits
+        #[allow(dead_code)]
+        #vis const KNOWN_BITS: #word_type = #known_bits;
+
+        /// Returns the raw bitflags
+        #vis fn #getter(&self) -> #word_type {
+            #get
+        }
+
+        /// Overwrites the raw bitflags, including the bits that aren't assigned to any packed field
+        #vis fn #setter(#receiver, bits: #word_type) {
+            #set
+        }
+    })
+}
+
+/// An expression of the bitflags with the given bits set, counting from the least significant bit of the
+/// first word
+fn storage_value(access: Access, storage: Storage, bits: &[usize]) -> TokenStream {
+    let word = storage.word();
+    let width = word.bit_width() as usize;
+    let mut words = match storage {
+        Storage::Int(_) => vec![0u128],
+        Storage::Words(_, words) => vec![0u128; words],
+    };
+    for bit in bits {
+        words[bit / width] |= 1 << (bit % width);
+    }
+    let mut words = words
+        .into_iter()
+        .map(|bits| access.new_word(word, Literal::u128_unsuffixed(bits).into_token_stream()));
+    match storage {
+        Storage::Int(_) => words.next().unwrap(),
+        Storage::Words(..) => quote! { [#(#words),*] },
     }
 }
 
-fn is_bool_type(ty: &Type) -> bool {
-    if let Type::Path(p) = ty {
-        p.path.is_ident("bool")
-    } else {
-        false
+/// Returns the definition of a plain copy of the bitflags with the same accessors, used to read or
+/// update several fields at once with atomic or volatile storage, along with the methods doing so
+fn snapshot(
+    config: &GlobalConfig,
+    vis: &Visibility,
+    ident: &Ident,
+    split: &SplitFields,
+    word: PackedType,
+    packed_path: &TokenStream,
+) -> syn::Result<(TokenStream, TokenStream)> {
+    let snapshot = format_ident!("{ident}Snapshot");
+    let mut functions = vec![];
+    for packed in &split.to_convert {
+        let accessors = packed.accessors(
+            config,
+            &packed.field.vis,
+            &quote! { self.0 },
+            Storage::Int(word),
+            Access::plain(),
+        )?;
+        functions.extend(accessors.into_iter().map(Accessor::into_method));
     }
+
+    let field = &config.field_name.0;
+    let (batch_fn, methods) = match config.storage_mode {
+        StorageMode::Atomic => atomic_methods(config, vis, &snapshot, packed_path),
+        StorageMode::Volatile => volatile_methods(config, vis, &snapshot, packed_path),
+        mode => unreachable!("no snapshot for storage mode {mode:?}"),
+    };
+    let doc =
+        format!("A copy of the packed fields of [`{ident}`], see [`{ident}::{batch_fn}_{field}`]");
+    let definition = quote! {
+        #[doc = #doc]
+        #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+        #vis struct #snapshot(#word);
+
+        impl #snapshot {
+            #(#functions)*
+        }
+    };
+    Ok((definition, methods))
+}
+
+/// Returns the name of the method updating several fields at once with atomic storage, along with the
+/// methods reading and updating the snapshot
+fn atomic_methods(
+    config: &GlobalConfig,
+    vis: &Visibility,
+    snapshot: &Ident,
+    packed_path: &TokenStream,
+) -> (&'static str, TokenStream) {
+    let access = Access::new(config);
+    let (set, load) = (access.set_ordering(), access.load_ordering());
+    let field = &config.field_name.0;
+    let load_fn = format_ident!("load_{field}");
+    let update_fn = format_ident!("update_{field}");
+    let compare_exchange_fn = format_ident!("compare_exchange_{field}");
+    let methods = quote! {
+        /// Reads all packed fields at once
+        #vis fn #load_fn(&self) -> #snapshot {
+            #snapshot(self.#packed_path.load(#load))
+        }
+
+        /// Atomically updates several packed fields at once by calling `f` on a copy of them, calling it
+        /// again if they were modified concurrently. Returns the packed fields before the update.
+        #vis fn #update_fn(&self, mut f: impl ::core::ops::FnMut(&mut #snapshot)) -> #snapshot {
+            let previous = self
+                .#packed_path
+                .fetch_update(#set, #load, |word| {
+                    let mut snapshot = #snapshot(word);
+                    f(&mut snapshot);
+                    ::core::option::Option::Some(snapshot.0)
+                })
+                .unwrap_or_else(|word| word);
+            #snapshot(previous)
+        }
+
+        /// Replaces the packed fields with `new` if they are equal to `current`. Returns the previous
+        /// packed fields, in `Ok` if they were replaced and in `Err` otherwise.
+        #vis fn #compare_exchange_fn(
+            &self,
+            current: #snapshot,
+            new: #snapshot,
+        ) -> ::core::result::Result<#snapshot, #snapshot> {
+            self.#packed_path
+                .compare_exchange(current.0, new.0, #set, #load)
+                .map(#snapshot)
+                .map_err(#snapshot)
+        }
+    };
+    ("update", methods)
+}
+
+/// Returns the name of the method updating several fields at once with volatile storage, along with the
+/// methods reading, writing and modifying the snapshot
+fn volatile_methods(
+    config: &GlobalConfig,
+    vis: &Visibility,
+    snapshot: &Ident,
+    packed_path: &TokenStream,
+) -> (&'static str, TokenStream) {
+    let field = &config.field_name.0;
+    let read_fn = format_ident!("read_{field}");
+    let write_fn = format_ident!("write_{field}");
+    let modify_fn = format_ident!("modify_{field}");
+    let place = quote! { self.#packed_path };
+    let read_volatile = Access::read_volatile(&place);
+    let write_volatile = Access::write_volatile(&place, &quote! { value.0 });
+    let methods = quote! {
+        /// Reads all packed fields at once, with a single volatile read
+        #vis fn #read_fn(&self) -> #snapshot {
+            #snapshot(#read_volatile)
+        }
+
+        /// Writes all packed fields at once, with a single volatile write
+        #vis fn #write_fn(&mut self, value: #snapshot) {
+            #write_volatile
+        }
+
+        /// Updates several packed fields at once by calling `f` on a copy of them, with a single volatile
+        /// read followed by a single volatile write. Returns the packed fields before the update.
+        #vis fn #modify_fn(&mut self, f: impl ::core::ops::FnOnce(&mut #snapshot)) -> #snapshot {
+            let previous = self.#read_fn();
+            let mut snapshot = previous;
+            f(&mut snapshot);
+            self.#write_fn(snapshot);
+            previous
+        }
+    };
+    ("modify", methods)
 }
@@ -0,0 +1,445 @@
+use proc_macro2::{Ident, Literal, TokenStream};
+use quote::{format_ident, quote};
+use syn::{Attribute, Error, Generics, Path, Token};
+use syn::ext::IdentExt;
+use syn::punctuated::Punctuated;
+
+use crate::pack_bools::config::{GlobalConfig, SerdeFormat, Storage};
+use crate::pack_bools::fields::{FieldKind, PackedField, SplitFields};
+use crate::pack_bools::storage::Access;
+use crate::pack_bools::storage_value;
+
+/// Returns the attributes of the field containing the bitflags, the `Serialize` and `Deserialize`
+/// impls of the newtype, and the associated functions the derived impls of the struct use with inline
+/// bitflags, as requested with `serde`
+pub fn serde_impls(
+    config: &GlobalConfig,
+    attrs: &[Attribute],
+    ident: &Ident,
+    generics: &Generics,
+    packed_type: &TokenStream,
+    split: &SplitFields,
+    storage: Storage,
+) -> syn::Result<(TokenStream, TokenStream, TokenStream)> {
+    let empty = || (TokenStream::new(), TokenStream::new(), TokenStream::new());
+    let Some(format) = config.serde else {
+        return Ok(empty());
+    };
+    let is_newtype = config.gen_type.is_newtype();
+    if format == SerdeFormat::Compact {
+        // Inline bitflags are serialized as they are by the derived impls of the struct
+        return Ok(if is_newtype {
+            (
+                TokenStream::new(),
+                compact_impls(packed_type),
+                TokenStream::new(),
+            )
+        } else {
+            empty()
+        });
+    }
+
+    let (serialize, deserialize) = (derives(attrs, "Serialize")?, derives(attrs, "Deserialize")?);
+    if split.is_tuple && (serialize || deserialize) {
+        // The packed fields are flattened into the struct, which serde only supports for named fields
+        return Err(Error::new(
+            ident.span(),
+            "#[pack_bools(serde)] can't flatten the packed fields into a tuple struct, use \
+            #[pack_bools(serde = compact)] instead",
+        ));
+    }
+    let fields: Vec<_> = split
+        .to_convert
+        .iter()
+        .filter(|packed| !packed.local.reserved)
+        .collect();
+    let access = Access::new(config);
+    let name = if is_newtype {
+        packed_type.to_string()
+    } else {
+        ident.unraw().to_string()
+    };
+    let default_bits: Vec<_> = split
+        .to_convert
+        .iter()
+        .flat_map(|packed| packed.default_bits(storage))
+        .collect();
+    let default = storage_value(Access::plain(), storage, &default_bits);
+    let convert = access.convert_plain(storage, quote! { bits });
+
+    if is_newtype {
+        let serialize_body = serialize_body(&name, &fields, storage, access, &quote! { self.0 });
+        let deserialize_body = deserialize_body(&name, &fields, storage, &default);
+        let attrs = (serialize || deserialize).then(|| quote! { #[serde(flatten)] });
+        let impls = quote! {
+            impl ::serde::Serialize for #packed_type {
+                fn serialize<S: ::serde::Serializer>(
+                    &self,
+                    serializer: S,
+                ) -> ::core::result::Result<S::Ok, S::Error> {
+                    #serialize_body
+                }
+            }
+
+            impl<'de> ::serde::Deserialize<'de> for #packed_type {
+                fn deserialize<D: ::serde::Deserializer<'de>>(
+                    deserializer: D,
+                ) -> ::core::result::Result<Self, D::Error> {
+                    let bits = #deserialize_body?;
+                    ::core::result::Result::Ok(Self(#convert))
+                }
+            }
+        };
+        return Ok((attrs.into_iter().collect(), impls, TokenStream::new()));
+    }
+
+    // Inline bitflags are (de)serialized by associated functions of the struct, flattened into it
+    let field = &config.field_name.0;
+    let turbofish = generics.split_for_impl().1.as_turbofish();
+    let path = |function: &Ident| quote! { #ident #turbofish :: #function }.to_string();
+    let mut serde_attrs = vec![quote! { flatten }];
+    let mut functions = TokenStream::new();
+    if serialize {
+        let serialize_fn = format_ident!("__serialize_{field}");
+        let serialize_path = path(&serialize_fn);
+        let body = serialize_body(&name, &fields, storage, access, &quote! { (*packed) });
+        serde_attrs.push(quote! { serialize_with = #serialize_path });
+        functions.extend(quote! {
+            fn #serialize_fn<S: ::serde::Serializer>(
+                packed: &#packed_type,
+                serializer: S,
+            ) -> ::core::result::Result<S::Ok, S::Error> {
+                #body
+            }
+        });
+    }
+    if deserialize {
+        let deserialize_fn = format_ident!("__deserialize_{field}");
+        let deserialize_path = path(&deserialize_fn);
+        let body = deserialize_body(&name, &fields, storage, &default);
+        serde_attrs.push(quote! { deserialize_with = #deserialize_path });
+        functions.extend(quote! {
+            fn #deserialize_fn<'de, D: ::serde::Deserializer<'de>>(
+                deserializer: D,
+            ) -> ::core::result::Result<#packed_type, D::Error> {
+                let bits = #body?;
+                ::core::result::Result::Ok(#convert)
+            }
+        });
+    }
+    let attrs = (serialize || deserialize)
+        .then(|| quote! { #[serde(#(#serde_attrs),*)] })
+        .into_iter()
+        .collect();
+    Ok((attrs, TokenStream::new(), functions))
+}
+
+/// Returns the `Serialize` and `Deserialize` impls of the newtype with `serde = compact`, which
+/// (de)serialize the raw bitflags
+fn compact_impls(newtype: &TokenStream) -> TokenStream {
+    quote! {
+        impl ::serde::Serialize for #newtype {
+            fn serialize<S: ::serde::Serializer>(
+                &self,
+                serializer: S,
+            ) -> ::core::result::Result<S::Ok, S::Error> {
+                ::serde::Serialize::serialize(&self.0, serializer)
+            }
+        }
+
+        impl<'de> ::serde::Deserialize<'de> for #newtype {
+            fn deserialize<D: ::serde::Deserializer<'de>>(
+                deserializer: D,
+            ) -> ::core::result::Result<Self, D::Error> {
+                ::serde::Deserialize::deserialize(deserializer).map(Self)
+            }
+        }
+    }
+}
+
+/// Statements serializing the packed fields at `packed` as a struct named `name` into `serializer`
+fn serialize_body(
+    name: &str,
+    fields: &[&PackedField],
+    storage: Storage,
+    access: Access,
+    packed: &TokenStream,
+) -> TokenStream {
+    let len = fields.len();
+    let fields = fields.iter().map(|packed_field| {
+        let key = packed_field.external_name();
+        let value = packed_field.value(storage, access, packed);
+        let value = if let FieldKind::Array(_) = packed_field.kind {
+            // Serialized as a sequence, as serde only implements `Serialize` for arrays of up to 32
+            // elements
+            quote! { &#value[..] }
+        } else if let FieldKind::Enum(_) = packed_field.kind {
+            let msg = format!("invalid value of `{key}`");
+            quote! {
+                match #value {
+                    ::core::result::Result::Ok(value) => value,
+                    ::core::result::Result::Err(_) => {
+                        return ::core::result::Result::Err(
+                            <S::Error as ::serde::ser::Error>::custom(#msg),
+                        );
+                    }
+                }
+            }
+        } else {
+            value
+        };
+        quote! {
+            let value = #value;
+            ::serde::ser::SerializeStruct::serialize_field(&mut state, #key, &value)?;
+        }
+    });
+    quote! {
+        let mut state = ::serde::Serializer::serialize_struct(serializer, #name, #len)?;
+        #(#fields)*
+        ::serde::ser::SerializeStruct::end(state)
+    }
+}
+
+/// An expression deserializing the packed fields as a struct named `name` from `deserializer`, into
+/// plain bitflags starting from `default`. Unknown fields are ignored, and missing fields keep their
+/// default value.
+fn deserialize_body(
+    name: &str,
+    fields: &[&PackedField],
+    storage: Storage,
+    default: &TokenStream,
+) -> TokenStream {
+    let access = Access::plain();
+    let word_type = access.storage_type(storage);
+    let keys: Vec<_> = fields.iter().map(|packed| packed.external_name()).collect();
+    let byte_keys = keys.iter().map(|key| Literal::byte_string(key.as_bytes()));
+    let indices: Vec<_> = (0..fields.len()).collect();
+    let len = fields.len() as u64;
+    let expecting = format!("the packed fields of `{name}`");
+
+    // Statements writing the deserialized `value` of each field to `packed`, failing with `A::Error`
+    let assigns: Vec<_> = fields
+        .iter()
+        .map(|packed| {
+            let check = packed.max_value().map(|max| {
+                let msg = format!(
+                    "{{}} doesn't fit in the bits of `{}`",
+                    packed.external_name()
+                );
+                quote! {
+                    if value > #max {
+                        return ::core::result::Result::Err(
+                            <A::Error as ::serde::de::Error>::custom(
+                                ::core::format_args!(#msg, value),
+                            ),
+                        );
+                    }
+                }
+            });
+            let ty = packed.value_type();
+            // Arrays are deserialized from a sequence, like they are serialized
+            let (ty, unwrap) = match packed.kind {
+                FieldKind::Array(len) => (
+                    quote! { BoolArray<#len> },
+                    Some(quote! { let value = value.0; }),
+                ),
+                _ => (ty, None),
+            };
+            let assign = packed.assign(storage, access, &quote! { packed });
+            (ty, quote! { #unwrap #check #assign })
+        })
+        .collect();
+    let map_fields = assigns.iter().zip(&indices).map(|((ty, assign), index)| {
+        quote! {
+            ::core::option::Option::Some(#index) => {
+                let value: #ty = ::serde::de::MapAccess::next_value(&mut map)?;
+                #assign
+            }
+        }
+    });
+    let seq_fields = assigns.iter().zip(&indices).map(|((ty, assign), index)| {
+        quote! {
+            let value: #ty = match ::serde::de::SeqAccess::next_element(&mut seq)? {
+                ::core::option::Option::Some(value) => value,
+                ::core::option::Option::None => {
+                    return ::core::result::Result::Err(
+                        <A::Error as ::serde::de::Error>::invalid_length(#index, &self),
+                    );
+                }
+            };
+            #assign
+        }
+    });
+
+    let bool_array = fields
+        .iter()
+        .any(|packed| matches!(packed.kind, FieldKind::Array(_)))
+        .then(bool_array);
+
+    quote! {
+        {
+            #bool_array
+
+            /// The index of a packed field, `None` for unknown fields
+            struct Key(::core::option::Option<usize>);
+
+            impl<'k> ::serde::Deserialize<'k> for Key {
+                fn deserialize<D: ::serde::Deserializer<'k>>(
+                    deserializer: D,
+                ) -> ::core::result::Result<Self, D::Error> {
+                    struct KeyVisitor;
+
+                    impl ::serde::de::Visitor<'_> for KeyVisitor {
+                        type Value = Key;
+
+                        fn expecting(
+                            &self,
+                            f: &mut ::core::fmt::Formatter<'_>,
+                        ) -> ::core::fmt::Result {
+                            f.write_str("a field identifier")
+                        }
+
+                        fn visit_u64<E: ::serde::de::Error>(
+                            self,
+                            index: u64,
+                        ) -> ::core::result::Result<Key, E> {
+                            ::core::result::Result::Ok(Key(
+                                (index < #len).then_some(index as usize),
+                            ))
+                        }
+
+                        fn visit_str<E: ::serde::de::Error>(
+                            self,
+                            key: &str,
+                        ) -> ::core::result::Result<Key, E> {
+                            ::core::result::Result::Ok(Key(match key {
+                                #(#keys => ::core::option::Option::Some(#indices),)*
+                                _ => ::core::option::Option::None,
+                            }))
+                        }
+
+                        fn visit_bytes<E: ::serde::de::Error>(
+                            self,
+                            key: &[u8],
+                        ) -> ::core::result::Result<Key, E> {
+                            ::core::result::Result::Ok(Key(match key {
+                                #(#byte_keys => ::core::option::Option::Some(#indices),)*
+                                _ => ::core::option::Option::None,
+                            }))
+                        }
+                    }
+
+                    ::serde::Deserializer::deserialize_identifier(deserializer, KeyVisitor)
+                }
+            }
+
+            struct Visitor;
+
+            impl<'v> ::serde::de::Visitor<'v> for Visitor {
+                type Value = #word_type;
+
+                fn expecting(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    f.write_str(#expecting)
+                }
+
+                fn visit_map<A: ::serde::de::MapAccess<'v>>(
+                    self,
+                    mut map: A,
+                ) -> ::core::result::Result<Self::Value, A::Error> {
+                    let mut packed: #word_type = #default;
+                    while let ::core::option::Option::Some(key) =
+                        ::serde::de::MapAccess::next_key::<Key>(&mut map)?
+                    {
+                        match key.0 {
+                            #(#map_fields)*
+                            _ => {
+                                ::serde::de::MapAccess::next_value::<::serde::de::IgnoredAny>(
+                                    &mut map,
+                                )?;
+                            }
+                        }
+                    }
+                    ::core::result::Result::Ok(packed)
+                }
+
+                fn visit_seq<A: ::serde::de::SeqAccess<'v>>(
+                    self,
+                    mut seq: A,
+                ) -> ::core::result::Result<Self::Value, A::Error> {
+                    let mut packed: #word_type = #default;
+                    #(#seq_fields)*
+                    ::core::result::Result::Ok(packed)
+                }
+            }
+
+            const FIELDS: &[&str] = &[#(#keys),*];
+            ::serde::Deserializer::deserialize_struct(deserializer, #name, FIELDS, Visitor)
+        }
+    }
+}
+
+/// The definition of `BoolArray<N>`, deserializing a `[bool; N]` from a sequence of any length, as
+/// serde only implements `Deserialize` for arrays of up to 32 elements
+fn bool_array() -> TokenStream {
+    quote! {
+        struct BoolArray<const N: usize>([bool; N]);
+
+        impl<'a, const N: usize> ::serde::Deserialize<'a> for BoolArray<N> {
+            fn deserialize<D: ::serde::Deserializer<'a>>(
+                deserializer: D,
+            ) -> ::core::result::Result<Self, D::Error> {
+                struct ArrayVisitor<const N: usize>;
+
+                impl<'v, const N: usize> ::serde::de::Visitor<'v> for ArrayVisitor<N> {
+                    type Value = BoolArray<N>;
+
+                    fn expecting(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        ::core::write!(f, "an array of {} bools", N)
+                    }
+
+                    fn visit_seq<A: ::serde::de::SeqAccess<'v>>(
+                        self,
+                        mut seq: A,
+                    ) -> ::core::result::Result<Self::Value, A::Error> {
+                        let mut values = [false; N];
+                        for (index, value) in values.iter_mut().enumerate() {
+                            *value = match ::serde::de::SeqAccess::next_element(&mut seq)? {
+                                ::core::option::Option::Some(value) => value,
+                                ::core::option::Option::None => {
+                                    return ::core::result::Result::Err(
+                                        <A::Error as ::serde::de::Error>::invalid_length(index, &self),
+                                    );
+                                }
+                            };
+                        }
+                        if ::serde::de::SeqAccess::next_element::<::serde::de::IgnoredAny>(&mut seq)?
+                            .is_some()
+                        {
+                            return ::core::result::Result::Err(
+                                <A::Error as ::serde::de::Error>::invalid_length(N + 1, &self),
+                            );
+                        }
+                        ::core::result::Result::Ok(BoolArray(values))
+                    }
+                }
+
+                ::serde::Deserializer::deserialize_seq(deserializer, ArrayVisitor)
+            }
+        }
+    }
+}
+
+/// Whether the `#[derive(..)]` attributes in `attrs` include `name`
+fn derives(attrs: &[Attribute], name: &str) -> syn::Result<bool> {
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("derive")) {
+        let paths = attr.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)?;
+        if paths.iter().any(|path| {
+            path.segments
+                .last()
+                .is_some_and(|segment| segment.ident == name)
+        }) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
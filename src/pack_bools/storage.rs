@@ -0,0 +1,230 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+
+use crate::pack_bools::config::{GlobalConfig, PackedType, Storage, StorageMode};
+
+/// How the generated code accesses the words of the bitflags
+#[derive(Copy, Clone)]
+pub struct Access<'a> {
+    pub mode: StorageMode,
+    /// The `Ordering` of atomic operations, `SeqCst` if not given
+    ordering: Option<&'a Ident>,
+}
+
+impl<'a> Access<'a> {
+    pub fn new(config: &'a GlobalConfig) -> Self {
+        Self {
+            mode: config.storage_mode,
+            ordering: config.ordering.as_ref(),
+        }
+    }
+
+    /// Access to plain words, regardless of the configured storage mode
+    pub fn plain() -> Self {
+        Self {
+            mode: StorageMode::Plain,
+            ordering: None,
+        }
+    }
+
+    /// The type of the field containing the bitflags (or of the field of the newtype)
+    pub fn storage_type(self, storage: Storage) -> TokenStream {
+        match storage {
+            Storage::Int(ty) => self.word_type(ty),
+            Storage::Words(ty, words) => {
+                let ty = self.word_type(ty);
+                quote! { [#ty; #words] }
+            }
+        }
+    }
+
+    fn word_type(self, ty: PackedType) -> TokenStream {
+        match self.mode {
+            StorageMode::Plain | StorageMode::Volatile => quote! { #ty },
+            StorageMode::Atomic => {
+                let atomic = format_ident!("AtomicU{}", ty.bit_width());
+                quote! { ::core::sync::atomic::#atomic }
+            }
+            StorageMode::Cell => quote! { ::core::cell::Cell<#ty> },
+        }
+    }
+
+    /// An expression creating a word of type `ty` holding `value`
+    pub fn new_word(self, ty: PackedType, value: TokenStream) -> TokenStream {
+        match self.mode {
+            StorageMode::Plain | StorageMode::Volatile => value,
+            StorageMode::Atomic | StorageMode::Cell => {
+                let ty = self.word_type(ty);
+                quote! { <#ty>::new(#value) }
+            }
+        }
+    }
+
+    /// An expression converting the plain bitflags `plain` to bitflags accessed this way
+    pub fn convert_plain(self, storage: Storage, plain: TokenStream) -> TokenStream {
+        match (self.mode, storage) {
+            (StorageMode::Plain | StorageMode::Volatile, _) => plain,
+            (_, Storage::Int(word)) => self.new_word(word, plain),
+            (_, Storage::Words(word, _)) => {
+                let new_word = self.new_word(word, quote! { word });
+                quote! { #plain.map(|word| #new_word) }
+            }
+        }
+    }
+
+    /// An expression of the current value of the word at `place`
+    pub fn get(self, place: &TokenStream) -> TokenStream {
+        match self.mode {
+            StorageMode::Plain => place.clone(),
+            StorageMode::Atomic => {
+                let ordering = self.load_ordering();
+                quote! { #place.load(#ordering) }
+            }
+            StorageMode::Cell => quote! { #place.get() },
+            StorageMode::Volatile => Self::read_volatile(place),
+        }
+    }
+
+    /// A statement overwriting the word at `place` with `value`
+    pub fn set(self, place: &TokenStream, value: &TokenStream) -> TokenStream {
+        match self.mode {
+            StorageMode::Plain => quote! { #place = #value; },
+            StorageMode::Atomic => {
+                let ordering = self.store_ordering();
+                quote! { #place.store(#value, #ordering); }
+            }
+            StorageMode::Cell => quote! { #place.set(#value); },
+            StorageMode::Volatile => {
+                let write_volatile = Self::write_volatile(place, value);
+                quote! { #write_volatile; }
+            }
+        }
+    }
+
+    /// An expression evaluating `read`, which is given an expression of the current value of the word at
+    /// `place`
+    pub fn load(
+        self,
+        place: &TokenStream,
+        read: impl FnOnce(&TokenStream) -> TokenStream,
+    ) -> TokenStream {
+        if self.mode == StorageMode::Plain {
+            return read(place);
+        }
+        let get = self.get(place);
+        let read = read(&quote! { word });
+        quote! {
+            {
+                let word = #get;
+                #read
+            }
+        }
+    }
+
+    /// Statements applying `write`, which is given a place holding the word, to the word at `place`
+    pub fn store(
+        self,
+        place: &TokenStream,
+        write: impl FnOnce(&TokenStream) -> TokenStream,
+    ) -> TokenStream {
+        match self.mode {
+            StorageMode::Plain => write(place),
+            StorageMode::Atomic => {
+                let swap = self.swap(place, write);
+                quote! { #swap; }
+            }
+            StorageMode::Cell | StorageMode::Volatile => {
+                let get = self.get(place);
+                let set = self.set(place, &quote! { word });
+                let write = write(&quote! { word });
+                quote! {
+                    let mut word = #get;
+                    #write
+                    #set
+                }
+            }
+        }
+    }
+
+    /// An expression applying `write` to the word at `place`, like [`Access::store`] (atomically with
+    /// atomic storage), and evaluating to the previous value of the word. Only available for shared
+    /// storage modes.
+    pub fn swap(
+        self,
+        place: &TokenStream,
+        write: impl FnOnce(&TokenStream) -> TokenStream,
+    ) -> TokenStream {
+        assert!(
+            self.mode.is_shared(),
+            "swapping requires a shared storage mode"
+        );
+        let write = write(&quote! { word });
+        if self.mode == StorageMode::Cell {
+            return quote! {
+                {
+                    let mut word = #place.get();
+                    let previous = word;
+                    #write
+                    #place.set(word);
+                    previous
+                }
+            };
+        }
+        let (set, fetch) = (self.set_ordering(), self.load_ordering());
+        quote! {
+            #place
+                .fetch_update(#set, #fetch, |mut word| {
+                    #write
+                    ::core::option::Option::Some(word)
+                })
+                .unwrap_or_else(|word| word)
+        }
+    }
+
+    /// An expression reading the word at `place` with a single volatile read
+    pub fn read_volatile(place: &TokenStream) -> TokenStream {
+        // Reading through a reference is always sound, the volatile read only prevents the compiler from
+        // eliding or merging it
+        quote! {
+            unsafe { ::core::ptr::read_volatile(&#place) }
+        }
+    }
+
+    /// An expression writing `value` to the word at `place` with a single volatile write
+    pub fn write_volatile(place: &TokenStream, value: &TokenStream) -> TokenStream {
+        quote! {
+            unsafe { ::core::ptr::write_volatile(&mut #place, #value) }
+        }
+    }
+
+    /// The ordering of operations both reading and writing the bitflags
+    pub fn set_ordering(self) -> TokenStream {
+        let ordering = self
+            .ordering
+            .cloned()
+            .unwrap_or_else(|| format_ident!("SeqCst"));
+        quote! { ::core::sync::atomic::Ordering::#ordering }
+    }
+
+    /// The ordering of operations only writing to the bitflags, which can't be `Acquire` or `AcqRel`
+    pub fn store_ordering(self) -> TokenStream {
+        let ordering = match self.ordering.map(Ident::to_string).as_deref() {
+            Some("Acquire") => format_ident!("Relaxed"),
+            Some("AcqRel") => format_ident!("Release"),
+            Some(_) => self.ordering.cloned().unwrap(),
+            None => format_ident!("SeqCst"),
+        };
+        quote! { ::core::sync::atomic::Ordering::#ordering }
+    }
+
+    /// The ordering of operations only reading from the bitflags, which can't be `Release` or `AcqRel`
+    pub fn load_ordering(self) -> TokenStream {
+        let ordering = match self.ordering.map(Ident::to_string).as_deref() {
+            Some("Release") => format_ident!("Relaxed"),
+            Some("AcqRel") => format_ident!("Acquire"),
+            Some(_) => self.ordering.cloned().unwrap(),
+            None => format_ident!("SeqCst"),
+        };
+        quote! { ::core::sync::atomic::Ordering::#ordering }
+    }
+}
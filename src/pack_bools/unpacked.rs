@@ -0,0 +1,362 @@
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{Attribute, Generics, Index, Member, Path, Token, Visibility};
+use syn::punctuated::Punctuated;
+
+use crate::pack_bools::config::{GlobalConfig, Storage};
+use crate::pack_bools::fields::{DeclaredField, FieldKind, SplitFields};
+use crate::pack_bools::storage::Access;
+use crate::pack_bools::storage_value;
+
+/// The helper attributes of well-known derives, which are removed from the struct when only the unpacked
+/// struct has these derives
+const KNOWN_HELPERS: &[(&str, &[&str])] = &[
+    ("Args", &["arg", "clap", "command", "group", "value"]),
+    ("Deserialize", &["serde"]),
+    ("JsonSchema", &["schemars", "serde"]),
+    ("Parser", &["arg", "clap", "command", "group", "value"]),
+    ("Serialize", &["serde"]),
+    ("Subcommand", &["arg", "clap", "command", "group", "value"]),
+    ("ValueEnum", &["clap", "value"]),
+];
+
+/// The helper attributes of the derives `paths` listed in `KNOWN_HELPERS`
+fn known_helpers<'a>(paths: impl IntoIterator<Item = &'a Path>) -> Vec<&'static str> {
+    paths
+        .into_iter()
+        .filter_map(|path| path.segments.last())
+        .flat_map(|segment| {
+            KNOWN_HELPERS
+                .iter()
+                .filter(|(derive, _)| segment.ident == derive)
+                .flat_map(|(_, helpers)| helpers.iter().copied())
+        })
+        .collect()
+}
+
+/// Returns the name and the definition of the struct with the fields of the struct in declaration
+/// order, without packing them, if requested. It gets the attributes of the struct other than its
+/// `#[repr]`, including its docs (or docs about the conversions if it has none), along with the derives
+/// given with `derive_unpacked`.
+pub fn unpacked_struct(
+    config: &GlobalConfig,
+    attrs: &[Attribute],
+    vis: &Visibility,
+    ident: &Ident,
+    generics: &Generics,
+    split: &SplitFields,
+) -> Option<(Ident, TokenStream)> {
+    if config.unpacked.is_none() && config.unpacked_derives.is_none() {
+        return None;
+    }
+    let unpacked = config
+        .unpacked
+        .clone()
+        .flatten()
+        .unwrap_or_else(|| format_ident!("{ident}Unpacked"));
+    let derives = config
+        .unpacked_derives
+        .as_ref()
+        .map(|derives| quote! { #[derive(#(#derives),*)] });
+    let has_enums = split
+        .to_convert
+        .iter()
+        .any(|packed| matches!(packed.kind, FieldKind::Enum(_)));
+    let doc = if has_enums {
+        format!(
+            "The fields of [`{ident}`] without packing, convertible to it with `From` and from it with \
+            `TryFrom`"
+        )
+    } else {
+        format!("The fields of [`{ident}`] without packing, convertible to and from it with `From`")
+    };
+    let fields = split.declared.iter().map(|declared| match *declared {
+        DeclaredField::Kept(idx) => &split.to_keep[idx],
+        DeclaredField::Packed(idx) => &split.to_convert[idx].field,
+    });
+    let where_clause = &generics.where_clause;
+    let body = if split.is_tuple {
+        quote! { ( #(#fields),* ) #where_clause; }
+    } else {
+        quote! { #where_clause { #(#fields),* } }
+    };
+    // The docs of the struct are kept as they are, since derives such as clap's `Parser` use them
+    let has_docs = attrs.iter().any(|attr| attr.path().is_ident("doc"));
+    // The layout of the struct doesn't apply to the unpacked fields, such as `#[repr(transparent)]` for a
+    // struct holding only the bitflags
+    let attrs = attrs.iter().filter(|attr| !attr.path().is_ident("repr"));
+    let doc = (!has_docs).then(|| quote! { #[doc = #doc] });
+    let definition = quote! {
+        #doc
+        #derives
+        #(#attrs)*
+        #vis struct #unpacked #generics #body
+    };
+    Some((unpacked, definition))
+}
+
+/// Removes the helper attributes of the derives of the unpacked struct from the struct and its remaining
+/// fields, such as `#[arg(long)]`, as they are only valid on the unpacked struct. These are the helpers
+/// of the derives in `KNOWN_HELPERS` and the ones listed with `unpacked_helpers`, except for the helpers
+/// of the derives of the struct itself, which still use them.
+pub fn strip_helper_attributes(
+    config: &GlobalConfig,
+    attrs: &mut Vec<Attribute>,
+    split: &mut SplitFields,
+) -> syn::Result<()> {
+    let Some(unpacked_derives) = &config.unpacked_derives else {
+        return Ok(());
+    };
+    let mut kept = vec![];
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("derive")) {
+        let derives = attr.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)?;
+        kept.extend(known_helpers(&derives));
+    }
+    let mut helpers: Vec<_> = known_helpers(unpacked_derives)
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    helpers.extend(config.unpacked_helpers.iter().map(Ident::to_string));
+    helpers.retain(|helper| !kept.contains(&helper.as_str()));
+
+    let is_kept = |attr: &Attribute| !helpers.iter().any(|helper| attr.path().is_ident(helper));
+    attrs.retain(is_kept);
+    for field in &mut split.to_keep {
+        field.attrs.retain(is_kept);
+    }
+    Ok(())
+}
+
+/// Returns the `From` impls converting between the struct and its unpacked counterpart named
+/// `unpacked`, where the field containing the bitflags is of type `packed_type`. If the struct has
+/// enum fields, whose bits may not convert back to their type, it converts to its unpacked counterpart
+/// with `TryFrom` instead, giving the struct back as the error.
+pub fn conversions(
+    config: &GlobalConfig,
+    ident: &Ident,
+    generics: &Generics,
+    unpacked: &Ident,
+    split: &SplitFields,
+    storage: Storage,
+    packed_type: &TokenStream,
+) -> TokenStream {
+    let access = Access::new(config);
+    let member = &split.member;
+    let packed_path = if config.gen_type.is_inline() {
+        quote! { packed.#member }
+    } else {
+        quote! { packed.#member.0 }
+    };
+    // The member of each field of the unpacked struct, in declaration order
+    let unpacked_members: Vec<_> = split
+        .declared
+        .iter()
+        .enumerate()
+        .map(|(position, declared)| {
+            let ident = match *declared {
+                DeclaredField::Kept(idx) => &split.to_keep[idx].ident,
+                DeclaredField::Packed(idx) => &split.to_convert[idx].field.ident,
+            };
+            match ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(Index::from(position)),
+            }
+        })
+        .collect();
+
+    let fields: Vec<_> = unpacked_members
+        .iter()
+        .map(|field| Some(quote! { unpacked.#field }))
+        .collect();
+    let pack = pack_fields(config, split, storage, packed_type, &fields);
+
+    // Unpacking: the packed fields are read like their getters do. The enum fields are read first, so that
+    // the struct can be given back as the error if their bits don't convert back to their type.
+    let mut checks = vec![];
+    let unpacked_values = split
+        .declared
+        .iter()
+        .zip(unpacked_members)
+        .map(|(declared, field)| {
+            let value = match *declared {
+                DeclaredField::Kept(idx) => {
+                    let member = split.kept_member(idx);
+                    quote! { packed.#member }
+                }
+                DeclaredField::Packed(idx) => {
+                    let packed_field = &split.to_convert[idx];
+                    let value = packed_field.value(storage, access, &packed_path);
+                    if let FieldKind::Enum(_) = packed_field.kind {
+                        let checked = format_ident!("field_{idx}", span = Span::mixed_site());
+                        checks.push(quote! {
+                            let #checked = match #value {
+                                ::core::result::Result::Ok(value) => value,
+                                ::core::result::Result::Err(_) => {
+                                    return ::core::result::Result::Err(packed);
+                                }
+                            };
+                        });
+                        quote! { #checked }
+                    } else {
+                        value
+                    }
+                }
+            };
+            (field, value)
+        })
+        .collect();
+    let unpack = construct(split.is_tuple, unpacked_values);
+
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+    let unpack_impl = if checks.is_empty() {
+        quote! {
+            impl #impl_generics ::core::convert::From<#ident #type_generics> for #unpacked #type_generics
+            #where_clause
+            {
+                fn from(packed: #ident #type_generics) -> Self {
+                    #unpack
+                }
+            }
+        }
+    } else {
+        quote! {
+            impl #impl_generics ::core::convert::TryFrom<#ident #type_generics> for #unpacked #type_generics
+            #where_clause
+            {
+                type Error = #ident #type_generics;
+
+                fn try_from(
+                    packed: #ident #type_generics,
+                ) -> ::core::result::Result<Self, Self::Error> {
+                    #(#checks)*
+                    ::core::result::Result::Ok(#unpack)
+                }
+            }
+        }
+    };
+    quote! {
+        impl #impl_generics ::core::convert::From<#unpacked #type_generics> for #ident #type_generics
+        #where_clause
+        {
+            fn from(unpacked: #unpacked #type_generics) -> Self {
+                #pack
+            }
+        }
+
+        #unpack_impl
+    }
+}
+
+/// Returns the constructor taking the fields of the struct in declaration order, if requested. Reserved
+/// fields aren't taken and get their default value.
+pub fn constructor(
+    config: &GlobalConfig,
+    vis: &Visibility,
+    ident: &Ident,
+    split: &SplitFields,
+    storage: Storage,
+    packed_type: &TokenStream,
+) -> Option<TokenStream> {
+    let name = config.constructor.as_ref()?;
+    let name = name.clone().unwrap_or_else(|| format_ident!("new"));
+    let mut params = vec![];
+    let mut values = vec![];
+    for (position, declared) in split.declared.iter().enumerate() {
+        let field = match *declared {
+            DeclaredField::Kept(idx) => &split.to_keep[idx],
+            DeclaredField::Packed(idx) if split.to_convert[idx].local.reserved => {
+                values.push(None);
+                continue;
+            }
+            DeclaredField::Packed(idx) => &split.to_convert[idx].field,
+        };
+        let param = match &field.ident {
+            Some(ident) => ident.clone(),
+            None => format_ident!("field_{position}"),
+        };
+        let ty = &field.ty;
+        params.push(quote! { #param: #ty });
+        values.push(Some(quote! { #param }));
+    }
+    let body = pack_fields(config, split, storage, packed_type, &values);
+    let doc = format!(
+        "Creates a [`{ident}`] from its fields in declaration order, the reserved fields taking their \
+        default value"
+    );
+    Some(quote! {
+        #[doc = #doc]
+        #[allow(clippy::too_many_arguments)]
+        #vis fn #name(#(#params),*) -> Self {
+            #body
+        }
+    })
+}
+
+/// An expression of the struct built from `values`, the expressions of its fields in declaration order,
+/// where the field containing the bitflags is of type `packed_type`. The packed fields without a value
+/// get their default value.
+fn pack_fields(
+    config: &GlobalConfig,
+    split: &SplitFields,
+    storage: Storage,
+    packed_type: &TokenStream,
+    values: &[Option<TokenStream>],
+) -> TokenStream {
+    let access = Access::new(config);
+    let plain = Access::plain();
+    // Hygienic, so that it can't shadow a field (or a parameter) with the same name
+    let packed = Ident::new("packed", Span::mixed_site());
+
+    // The packed fields are written to plain bitflags, which are then converted to the storage
+    let mut kept_values = vec![];
+    let mut assigns = vec![];
+    for (declared, value) in split.declared.iter().zip(values) {
+        match (*declared, value) {
+            (DeclaredField::Kept(idx), Some(value)) => {
+                kept_values.push((split.kept_member(idx), value.clone()));
+            }
+            (DeclaredField::Packed(idx), Some(value)) => {
+                let assign = split.to_convert[idx].assign(storage, plain, &quote! { #packed });
+                assigns.push(quote! {
+                    {
+                        let value = #value;
+                        #assign
+                    }
+                });
+            }
+            (DeclaredField::Kept(_), None) => unreachable!("kept fields always have a value"),
+            (DeclaredField::Packed(_), None) => {}
+        }
+    }
+    let plain_type = plain.storage_type(storage);
+    let default_bits: Vec<_> = split
+        .to_convert
+        .iter()
+        .flat_map(|packed_field| packed_field.default_bits(storage))
+        .collect();
+    let default = storage_value(plain, storage, &default_bits);
+    let converted = access.convert_plain(storage, quote! { #packed });
+    let packed_value = if config.gen_type.is_inline() {
+        converted
+    } else {
+        quote! { #packed_type(#converted) }
+    };
+    kept_values.insert(split.position, (split.member.clone(), packed_value));
+    let construct = construct(split.is_tuple, kept_values);
+    quote! {
+        let mut #packed: #plain_type = #default;
+        #(#assigns)*
+        #construct
+    }
+}
+
+/// An expression of `Self` with the given fields, which are in order for tuple structs
+fn construct(is_tuple: bool, values: Vec<(Member, TokenStream)>) -> TokenStream {
+    if is_tuple {
+        let values = values.into_iter().map(|(_, value)| value);
+        quote! { Self( #(#values),* ) }
+    } else {
+        let (members, values): (Vec<_>, Vec<_>) = values.into_iter().unzip();
+        quote! { Self { #(#members: #values),* } }
+    }
+}
@@ -0,0 +1,84 @@
+use pack_bools::pack_bools;
+
+#[pack_bools]
+struct Mixer {
+    muted: bool,
+    channels_enabled: [bool; 16],
+    solo: bool,
+}
+
+#[test]
+fn test_array_type() {
+    let mixer = Mixer { packed_bools: 0 };
+    let _: u32 = mixer.packed_bools;
+}
+
+#[test]
+fn test_array_indexed() {
+    let mut mixer = Mixer { packed_bools: 0 };
+    mixer.set_channels_enabled(0, true);
+    mixer.set_channels_enabled(15, true);
+    mixer.set_solo(true);
+    assert!(mixer.get_channels_enabled(0));
+    assert!(!mixer.get_channels_enabled(1));
+    assert!(mixer.get_channels_enabled(15));
+    assert!(!mixer.get_muted());
+    assert!(mixer.get_solo());
+    assert_eq!(mixer.packed_bools, 1 << 1 | 1 << 16 | 1 << 17);
+    mixer.set_channels_enabled(0, false);
+    assert!(!mixer.get_channels_enabled(0));
+}
+
+#[test]
+fn test_array_whole() {
+    let mut mixer = Mixer { packed_bools: 0 };
+    let mut channels = [false; 16];
+    channels[3] = true;
+    channels[7] = true;
+    mixer.set_channels_enabled_array(channels);
+    assert_eq!(mixer.get_channels_enabled_array(), channels);
+    assert!(mixer.get_channels_enabled(3));
+    mixer.set_channels_enabled_array([true; 16]);
+    assert_eq!(mixer.get_channels_enabled_array(), [true; 16]);
+    assert!(!mixer.get_muted());
+    assert!(!mixer.get_solo());
+}
+
+#[test]
+#[should_panic(expected = "`channels_enabled` has a length of 16 but the index is 16")]
+fn test_array_out_of_range() {
+    let mixer = Mixer { packed_bools: 0 };
+    mixer.get_channels_enabled(16);
+}
+
+#[pack_bools(newtype)]
+#[derive(Default)]
+struct Defaults {
+    first: bool,
+    #[pack_bools(default = true)]
+    lights: [bool; 4],
+}
+
+#[test]
+fn test_array_defaults() {
+    let defaults = Defaults::default();
+    assert!(!defaults.get_first());
+    assert_eq!(defaults.get_lights_array(), [true; 4]);
+}
+
+#[pack_bools]
+enum Port {
+    Digital { pins: [bool; 8] },
+    Disabled,
+}
+
+#[test]
+fn test_array_enum() {
+    let mut port = Port::Digital { packed_bools: 0 };
+    assert!(port.set_pins(2, true));
+    assert_eq!(port.get_pins(2), Some(true));
+    assert_eq!(port.get_pins_array().map(|pins| pins[2]), Some(true));
+    port = Port::Disabled;
+    assert_eq!(port.get_pins(2), None);
+    assert!(!port.set_pins_array([true; 8]));
+}
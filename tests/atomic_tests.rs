@@ -0,0 +1,114 @@
+use std::sync::Arc;
+use std::thread;
+
+use pack_bools::pack_bools;
+
+#[pack_bools(storage = atomic)]
+struct Flags {
+    running: bool,
+    paused: bool,
+    mode: Option<bool>,
+}
+
+#[test]
+fn test_atomic_accessors() {
+    let flags = Flags {
+        packed_bools: std::sync::atomic::AtomicU8::new(0),
+    };
+    flags.set_running(true);
+    flags.set_mode(Some(false));
+    assert!(flags.get_running());
+    assert!(!flags.get_paused());
+    assert_eq!(flags.get_mode(), Some(false));
+
+    assert!(!flags.fetch_set_paused());
+    assert!(flags.fetch_set_paused());
+    assert!(flags.swap_paused(false));
+    assert_eq!(flags.swap_mode(None), Some(false));
+    assert_eq!(flags.get_mode(), None);
+    assert!(!flags.get_paused());
+}
+
+#[test]
+fn test_atomic_shared() {
+    let flags = Arc::new(Flags {
+        packed_bools: std::sync::atomic::AtomicU8::new(0),
+    });
+    let threads = [true, false]
+        .into_iter()
+        .map(|running| {
+            let flags = flags.clone();
+            thread::spawn(move || {
+                if running {
+                    flags.set_running(true);
+                } else {
+                    flags.set_paused(true);
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    // Neither update overwrote the other
+    assert!(flags.get_running());
+    assert!(flags.get_paused());
+}
+
+#[test]
+fn test_atomic_snapshot() {
+    let flags = Flags {
+        packed_bools: std::sync::atomic::AtomicU8::new(0),
+    };
+    let previous = flags.update_packed_bools(|snapshot| {
+        snapshot.set_running(true);
+        snapshot.set_mode(Some(true));
+    });
+    assert!(!previous.get_running());
+    let current = flags.load_packed_bools();
+    assert!(current.get_running());
+    assert_eq!(current.get_mode(), Some(true));
+
+    let mut new = current;
+    new.set_paused(true);
+    assert_eq!(
+        flags.compare_exchange_packed_bools(current, new),
+        Ok(current)
+    );
+    assert_eq!(flags.compare_exchange_packed_bools(current, new), Err(new));
+    assert!(flags.get_paused());
+}
+
+#[pack_bools(storage = atomic, ordering = Acquire, newtype)]
+struct Defaults {
+    a: bool,
+    #[pack_bools(default = true)]
+    b: bool,
+}
+
+#[test]
+fn test_atomic_newtype() {
+    let defaults = Defaults {
+        packed_bools: Default::default(),
+    };
+    assert!(!defaults.get_a());
+    assert!(defaults.get_b());
+    defaults.set_a(true);
+    assert!(defaults.get_a());
+}
+
+#[pack_bools(storage = atomic)]
+struct Wide {
+    flags: [bool; 70],
+}
+
+#[test]
+fn test_atomic_words() {
+    let wide = Wide {
+        packed_bools: Default::default(),
+    };
+    let _: &[std::sync::atomic::AtomicU64; 2] = &wide.packed_bools;
+    wide.set_flags(69, true);
+    assert!(wide.get_flags(69));
+    assert!(!wide.get_flags(0));
+}
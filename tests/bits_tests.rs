@@ -0,0 +1,80 @@
+use std::mem::size_of;
+
+use pack_bools::pack_bools;
+
+#[pack_bools]
+#[derive(Default)]
+struct Levels {
+    verbose: bool,
+    #[pack_bools(bits = 3)]
+    level: u8,
+    colors: bool,
+    #[pack_bools(bits = 16, get = pub counter)]
+    counter: u16,
+    unpacked: u8,
+}
+
+#[test]
+fn test_int_fields() {
+    let mut levels = Levels::default();
+    levels.set_verbose(true);
+    levels.set_level(5);
+    levels.set_colors(true);
+    levels.set_counter(0xABCD);
+    assert!(levels.get_verbose());
+    assert_eq!(levels.get_level(), 5);
+    assert!(levels.get_colors());
+    assert_eq!(levels.counter(), 0xABCD);
+    assert_eq!(
+        levels.packed_bools,
+        0b1 | 0b101 << 1 | 0b1 << 4 | 0xABCD << 5
+    );
+    assert_eq!(levels.unpacked, 0);
+
+    levels.set_level(0);
+    levels.set_counter(0);
+    assert!(levels.get_verbose());
+    assert!(levels.get_colors());
+    assert_eq!(levels.packed_bools, 0b1 | 0b1 << 4);
+}
+
+#[test]
+fn test_int_sizing() {
+    // 2 bools + 3 bits + 16 bits = 21 bits
+    assert_eq!(
+        size_of::<u32>(),
+        size_of_val(&Levels::default().packed_bools)
+    );
+}
+
+#[test]
+#[should_panic(expected = "8 doesn't fit in the 3 bits of `level`")]
+#[cfg(debug_assertions)]
+fn test_int_out_of_range() {
+    let mut levels = Levels::default();
+    levels.set_level(8);
+}
+
+#[pack_bools(newtype, type = u8)]
+enum Mode {
+    Off,
+    On {
+        #[pack_bools(bits = 2)]
+        speed: u8,
+        #[pack_bools(bits = 6)]
+        target: u64,
+    },
+}
+
+#[test]
+fn test_int_enum() {
+    let mut mode = Mode::On {
+        packed_bools: Default::default(),
+    };
+    assert!(mode.set_speed(3));
+    assert!(mode.set_target(63));
+    assert_eq!(mode.get_speed(), Some(3));
+    assert_eq!(mode.get_target(), Some(63));
+    mode = Mode::Off;
+    assert_eq!(mode.get_speed(), None);
+}
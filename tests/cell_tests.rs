@@ -0,0 +1,77 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use pack_bools::pack_bools;
+
+#[pack_bools(storage = cell)]
+struct Node {
+    visible: bool,
+    focused: bool,
+    #[pack_bools(bits = 3)]
+    depth: u8,
+}
+
+#[test]
+fn test_cell_accessors() {
+    let node = Rc::new(Node {
+        packed_bools: Cell::new(0),
+    });
+    let shared = node.clone();
+    shared.set_visible(true);
+    shared.set_depth(5);
+    assert!(node.get_visible());
+    assert!(!node.get_focused());
+    assert_eq!(node.get_depth(), 5);
+    assert_eq!(node.packed_bools.get(), 0b1_0100 | 1);
+
+    assert!(!node.fetch_set_focused());
+    assert!(node.get_focused());
+    assert_eq!(node.swap_depth(2), 5);
+    assert_eq!(node.get_depth(), 2);
+}
+
+#[pack_bools(storage = cell, newtype)]
+#[derive(Clone)]
+struct Widget {
+    #[pack_bools(default = true)]
+    enabled: bool,
+    hovered: bool,
+}
+
+#[test]
+fn test_cell_newtype() {
+    let widget = Widget {
+        packed_bools: Default::default(),
+    };
+    let copy = widget.clone();
+    widget.set_hovered(true);
+    widget.set_enabled(false);
+    assert!(widget.get_hovered());
+    assert!(!widget.get_enabled());
+    assert!(!copy.get_hovered());
+    assert!(copy.get_enabled());
+}
+
+#[pack_bools(storage = cell)]
+enum Shape {
+    Circle { filled: bool, outlined: bool },
+    Line(String, bool),
+}
+
+#[test]
+fn test_cell_enum() {
+    let shape = Shape::Circle {
+        packed_bools: Cell::new(0),
+    };
+    assert!(shape.set_filled(true));
+    assert_eq!(shape.get_filled(), Some(true));
+    assert_eq!(shape.get_outlined(), Some(false));
+    assert_eq!(shape.get_1(), None);
+
+    let line = Shape::Line(String::from("dashed"), Cell::new(0));
+    assert!(line.set_1(true));
+    assert_eq!(line.get_1(), Some(true));
+    if let Shape::Line(style, _) = line {
+        assert_eq!(style, "dashed");
+    }
+}
@@ -0,0 +1,8 @@
+use pack_bools::pack_bools;
+
+#[pack_bools(storage = atomic, type = u128)]
+struct Flags {
+    a: bool,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use pack_bools::pack_bools;
+
+#[pack_bools(type = u8)]
+struct MyBools {
+    a: bool,
+    #[pack_bools(bit = 8)]
+    b: bool,
+}
+
+fn main() {}
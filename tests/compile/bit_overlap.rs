@@ -0,0 +1,11 @@
+use pack_bools::pack_bools;
+
+#[pack_bools]
+struct MyBits {
+    #[pack_bools(bits = 4, bit = 2)]
+    a: u8,
+    #[pack_bools(bit = 5)]
+    b: bool,
+}
+
+fn main() {}
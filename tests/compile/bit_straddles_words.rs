@@ -0,0 +1,9 @@
+use pack_bools::pack_bools;
+
+#[pack_bools(type = [u8; 2])]
+struct MyBits {
+    #[pack_bools(bits = 4, bit = 6)]
+    a: u8,
+}
+
+fn main() {}
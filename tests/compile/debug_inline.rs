@@ -0,0 +1,9 @@
+use pack_bools::pack_bools;
+
+#[pack_bools(debug)]
+#[derive(Debug)]
+struct Flags {
+    a: bool,
+}
+
+fn main() {}
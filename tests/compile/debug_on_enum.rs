@@ -0,0 +1,9 @@
+use pack_bools::pack_bools;
+
+#[pack_bools(debug = struct)]
+enum State {
+    Idle,
+    Running { verbose: bool },
+}
+
+fn main() {}
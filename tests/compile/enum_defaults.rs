@@ -0,0 +1,11 @@
+use pack_bools::pack_bools;
+
+#[pack_bools(newtype)]
+enum State {
+    A {
+        #[pack_bools(default = true)]
+        a: bool,
+    },
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use pack_bools::pack_bools;
+
+#[pack_bools]
+enum TooLarge {
+    Small,
+    Large = 1 << 8,
+}
+
+fn main() {}
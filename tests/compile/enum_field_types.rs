@@ -0,0 +1,9 @@
+use pack_bools::pack_bools;
+
+#[pack_bools]
+enum State {
+    Idle { verbose: bool },
+    Running { verbose: Option<bool> },
+}
+
+fn main() {}
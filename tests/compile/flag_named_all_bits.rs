@@ -0,0 +1,9 @@
+use pack_bools::pack_bools;
+
+#[pack_bools(newtype)]
+struct Config {
+    verbose: bool,
+    all_bits: bool,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use pack_bools::pack_bools;
+
+#[pack_bools(flags)]
+struct Config {
+    a_b: bool,
+    a__b: bool,
+}
+
+fn main() {}
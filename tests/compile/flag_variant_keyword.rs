@@ -0,0 +1,21 @@
+use pack_bools::pack_bools;
+
+#[pack_bools(flags)]
+struct Config {
+    verbose: bool,
+    self_: bool,
+}
+
+#[pack_bools(flags)]
+struct Padded {
+    verbose: bool,
+    __: bool,
+}
+
+#[pack_bools(flags)]
+struct Constant {
+    verbose: bool,
+    a_l_l: bool,
+}
+
+fn main() {}
@@ -0,0 +1,27 @@
+use pack_bools::pack_bools;
+
+#[pack_bools]
+struct TooWide {
+    #[pack_bools(bits = 9)]
+    a: u8,
+}
+
+#[pack_bools]
+struct Signed {
+    #[pack_bools(bits = 2)]
+    a: i8,
+}
+
+#[pack_bools]
+struct OnBool {
+    #[pack_bools(bits = 1)]
+    a: bool,
+}
+
+#[pack_bools]
+struct Missing {
+    #[pack_bools(get = get_a)]
+    a: u8,
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use pack_bools::pack_bools;
+
+#[pack_bools]
+fn not_a_struct(a: bool) {}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use pack_bools::pack_bools;
+
+#[pack_bools(new)]
+enum State {
+    Idle,
+    Running { verbose: bool },
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use pack_bools::pack_bools;
+
+#[pack_bools(newtype)]
+enum Mode {
+    Fast,
+    Slow,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use pack_bools::pack_bools;
+
+#[pack_bools(storage = cell, ordering = SeqCst)]
+struct Flags {
+    a: bool,
+}
+
+fn main() {}
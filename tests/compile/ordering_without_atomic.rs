@@ -0,0 +1,8 @@
+use pack_bools::pack_bools;
+
+#[pack_bools(ordering = Relaxed)]
+struct Flags {
+    a: bool,
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use pack_bools::pack_bools;
+
+#[pack_bools(position = 2)]
+struct Flags(u32, bool);
+
+fn main() {}
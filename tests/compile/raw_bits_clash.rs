@@ -0,0 +1,9 @@
+use pack_bools::pack_bools;
+
+#[pack_bools(raw_bits)]
+struct Config {
+    verbose: bool,
+    packed_bits: bool,
+}
+
+fn main() {}
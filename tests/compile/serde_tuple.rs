@@ -0,0 +1,8 @@
+use pack_bools::pack_bools;
+use serde::Serialize;
+
+#[pack_bools(serde)]
+#[derive(Serialize)]
+struct Flags(u32, bool, bool);
+
+fn main() {}
@@ -0,0 +1,27 @@
+use std::fmt::Debug;
+
+use pack_bools::pack_bools;
+
+/// A named struct with a where clause
+#[pack_bools(newtype)]
+#[derive(Debug)]
+#[repr(C)]
+#[derive(Clone, Default)]
+pub struct Named<'a, T>
+where
+    T: Debug + Clone + Default,
+{
+    /// The value
+    value: T,
+    name: &'a str,
+    #[pack_bools(default = true)]
+    enabled: bool,
+    visible: bool,
+}
+
+fn main() {
+    let named = Named::<u8>::default();
+    assert!(named.get_enabled());
+    assert!(!named.get_visible());
+    let _ = format!("{:?} {:?} {}", named.clone(), named.value, named.name);
+}
@@ -0,0 +1,20 @@
+use std::fmt::Debug;
+
+use pack_bools::pack_bools;
+
+/// A tuple struct with a where clause
+#[pack_bools(position = 0)]
+#[derive(Debug)]
+#[repr(C)]
+#[derive(Clone, Default)]
+pub struct Tuple<T>(bool, T, bool)
+where
+    T: Debug + Clone + Default;
+
+fn main() {
+    let mut tuple = Tuple::<String>::default();
+    tuple.set_2(true);
+    assert!(!tuple.get_0());
+    assert!(tuple.get_2());
+    let _ = format!("{:?} {:?}", tuple.clone(), tuple.1);
+}
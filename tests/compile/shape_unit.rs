@@ -0,0 +1,25 @@
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+use pack_bools::pack_bools;
+
+/// A unit struct
+#[pack_bools]
+#[derive(Debug)]
+#[repr(C)]
+#[derive(Clone, Default)]
+pub struct Unit;
+
+/// A tuple struct without bools, to make sure generics are kept for the unit-like shapes too
+#[pack_bools]
+#[derive(Default)]
+pub struct Marker<T>(PhantomData<T>)
+where
+    T: Debug;
+
+fn main() {
+    let unit = Unit;
+    let _ = format!("{:?}", unit.clone());
+    let marker = Marker::<u8>::default();
+    let Marker(_, 0) = marker else { unreachable!() };
+}
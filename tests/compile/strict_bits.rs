@@ -0,0 +1,10 @@
+use pack_bools::pack_bools;
+
+#[pack_bools(strict_bits)]
+struct MyBools {
+    #[pack_bools(bit = 0)]
+    a: bool,
+    b: bool,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use pack_bools::pack_bools;
+
+#[pack_bools(type = u16)]
+struct MyBits {
+    a: bool,
+    #[pack_bools(bits = 8)]
+    b: u8,
+    #[pack_bools(bits = 8)]
+    c: u8,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use pack_bools::pack_bools;
+
+#[pack_bools(type = [u8; 1])]
+struct MyBools {
+    a: bool,
+    #[pack_bools(bits = 2)]
+    b: u8,
+    #[pack_bools(bits = 6)]
+    c: u8,
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+use pack_bools::pack_bools;
+
+#[pack_bools]
+struct InlineDefault {
+    #[pack_bools(default = false)]
+    a: Option<bool>,
+}
+
+#[pack_bools(newtype)]
+struct NoneOnBool {
+    #[pack_bools(default = none)]
+    a: bool,
+}
+
+#[pack_bools(newtype)]
+struct DefaultOnInt {
+    #[pack_bools(bits = 2, default = false)]
+    a: u8,
+}
+
+fn main() {}
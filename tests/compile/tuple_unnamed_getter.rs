@@ -0,0 +1,6 @@
+use pack_bools::pack_bools;
+
+#[pack_bools(get = %)]
+struct Flags(u32, bool, #[pack_bools(name = ok)] bool);
+
+fn main() {}
@@ -0,0 +1,10 @@
+use pack_bools::pack_bools;
+
+#[pack_bools(type = [u8; 4])]
+struct MyBits {
+    a: bool,
+    #[pack_bools(bits = 12)]
+    b: u16,
+}
+
+fn main() {}
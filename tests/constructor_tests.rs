@@ -0,0 +1,44 @@
+use std::cell::Cell;
+
+use pack_bools::pack_bools;
+
+#[pack_bools(new)]
+#[derive(Debug, PartialEq)]
+struct Config<'a> {
+    name: &'a str,
+    verbose: bool,
+    packed: Option<bool>,
+    #[pack_bools(bits = 3)]
+    depth: u8,
+    channels: [bool; 2],
+    #[pack_bools(reserved)]
+    _unused: bool,
+}
+
+#[test]
+fn test_constructor() {
+    let config = Config::new("test", true, Some(false), 5, [false, true]);
+    assert_eq!(config.name, "test");
+    assert!(config.get_verbose());
+    assert_eq!(config.get_packed(), Some(false));
+    assert_eq!(config.get_depth(), 5);
+    assert_eq!(config.get_channels_array(), [false, true]);
+}
+
+#[pack_bools(newtype, new = create, storage = cell, raw_bits)]
+struct Shared<T>(
+    T,
+    bool,
+    #[pack_bools(reserved, default = true)] bool,
+    Cell<u8>,
+);
+
+#[test]
+fn test_constructor_tuple() {
+    let shared = Shared::create(3, true, Cell::new(4));
+    assert_eq!(shared.0, 3);
+    assert!(shared.get_1());
+    assert_eq!(shared.1.get(), 4);
+    // The reserved field gets its default value
+    assert_eq!(shared.packed_bits(), 0b11);
+}
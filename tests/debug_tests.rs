@@ -0,0 +1,79 @@
+use pack_bools::pack_bools;
+
+#[pack_bools(newtype, debug)]
+#[derive(Debug)]
+struct Config<'a> {
+    verbose: bool,
+    name: &'a str,
+    level: Option<bool>,
+    #[pack_bools(get = is_forced, set = force)]
+    r#override: bool,
+}
+
+#[test]
+fn test_newtype_debug() {
+    let mut config = Config {
+        name: "test",
+        packed_bools: Default::default(),
+    };
+    config.set_verbose(true);
+    config.set_level(Some(false));
+    assert_eq!(config.name, "test");
+    assert_eq!(
+        format!("{:?}", config.packed_bools),
+        "ConfigPackedBools { verbose: true, level: Some(false), override: false }"
+    );
+    assert_eq!(
+        format!("{config:?}"),
+        "Config { name: \"test\", packed_bools: ConfigPackedBools { verbose: true, level: Some(false), override: false } }"
+    );
+}
+
+#[pack_bools(debug = struct)]
+#[derive(Clone, Debug, PartialEq)]
+struct Options<T> {
+    a: bool,
+    value: T,
+    channels: [bool; 3],
+    #[pack_bools(reserved)]
+    _unused: bool,
+}
+
+#[test]
+fn test_struct_debug() {
+    let mut options = Options {
+        value: 7,
+        packed_bools: 0,
+    };
+    options.set_channels(1, true);
+    assert_eq!(
+        format!("{options:?}"),
+        "Options { a: false, value: 7, channels: [false, true, false], _unused: false }"
+    );
+    assert_eq!(
+        format!("{options:#?}"),
+        "Options {
+    a: false,
+    value: 7,
+    channels: [
+        false,
+        true,
+        false,
+    ],
+    _unused: false,
+}"
+    );
+    // The other derives are kept
+    assert_eq!(options.clone(), options);
+}
+
+#[pack_bools(debug = struct, position = 0)]
+#[derive(Debug)]
+struct Tuple(String, bool, u8);
+
+#[test]
+fn test_tuple_debug() {
+    let mut tuple = Tuple(0, String::from("a"), 2);
+    tuple.set_1(true);
+    assert_eq!(format!("{tuple:?}"), "Tuple(\"a\", true, 2)");
+}
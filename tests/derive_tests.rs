@@ -0,0 +1,81 @@
+use std::cell::Cell;
+use std::collections::HashSet;
+
+use pack_bools::pack_bools;
+
+#[pack_bools(newtype)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct Key {
+    id: u32,
+    a: bool,
+    b: bool,
+}
+
+#[test]
+fn test_mirrored_derives() {
+    let mut first = Key {
+        id: 1,
+        packed_bools: Default::default(),
+    };
+    let mut second = first;
+    assert_eq!(first, second);
+    second.set_b(true);
+    assert_ne!(first, second);
+    assert!(first < second);
+    first.set_a(true);
+    assert!(first < second);
+    assert_eq!(HashSet::from([first, second, first]).len(), 2);
+}
+
+#[pack_bools(newtype, storage = cell)]
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Shared {
+    a: bool,
+}
+
+#[test]
+fn test_mirrored_derives_cell() {
+    let shared = Shared {
+        packed_bools: Default::default(),
+    };
+    let other = Shared {
+        packed_bools: Default::default(),
+    };
+    assert_eq!(shared, other);
+    other.set_a(true);
+    assert_ne!(shared, other);
+}
+
+#[pack_bools(newtype, newtype_derive(Clone, PartialEq))]
+#[derive(Clone, PartialEq)]
+struct Explicit {
+    a: bool,
+}
+
+#[test]
+fn test_newtype_derive() {
+    let explicit = Explicit {
+        packed_bools: Default::default(),
+    };
+    let mut copy = explicit.clone();
+    assert!(copy == explicit);
+    copy.set_a(true);
+    assert!(copy != explicit);
+}
+
+#[pack_bools(newtype)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Event {
+    Click { left: bool, double: bool },
+    Key(char, bool),
+}
+
+#[test]
+fn test_mirrored_derives_enum() {
+    let mut event = Event::Key('a', Default::default());
+    let previous = event;
+    event.set_1(true);
+    assert_ne!(event, previous);
+    assert_eq!(HashSet::from([event, previous, event]).len(), 2);
+    let _ = Cell::new(event);
+}
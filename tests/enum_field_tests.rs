@@ -0,0 +1,114 @@
+use pack_bools::pack_bools;
+
+#[pack_bools]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum LogLevel {
+    Error = 1,
+    Warn,
+    Info,
+}
+
+#[pack_bools]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u16)]
+enum Wide {
+    Small,
+    Large = 300,
+}
+
+/// A user-provided mapping, storing the enum inverted
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Mode {
+    Fast,
+    Slow,
+}
+
+impl From<Mode> for u8 {
+    fn from(value: Mode) -> Self {
+        match value {
+            Mode::Fast => 1,
+            Mode::Slow => 0,
+        }
+    }
+}
+
+impl TryFrom<u8> for Mode {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Mode::Slow),
+            1 => Ok(Mode::Fast),
+            _ => Err(()),
+        }
+    }
+}
+
+#[pack_bools]
+struct Settings {
+    verbose: bool,
+    #[pack_bools(enum, bits = 2)]
+    level: LogLevel,
+    #[pack_bools(enum, bits = 1)]
+    mode: Mode,
+    #[pack_bools(enum, bits = 9)]
+    wide: Wide,
+}
+
+#[test]
+fn test_enum_fields() {
+    let mut settings = Settings { packed_bools: 0 };
+    assert_eq!(settings.get_level(), Err(0));
+    assert_eq!(settings.get_mode(), Ok(Mode::Slow));
+    assert_eq!(settings.get_wide(), Ok(Wide::Small));
+
+    settings.set_verbose(true);
+    settings.set_level(LogLevel::Warn);
+    settings.set_mode(Mode::Fast);
+    settings.set_wide(Wide::Large);
+    assert!(settings.get_verbose());
+    assert_eq!(settings.get_level(), Ok(LogLevel::Warn));
+    assert_eq!(settings.get_mode(), Ok(Mode::Fast));
+    assert_eq!(settings.get_wide(), Ok(Wide::Large));
+    assert_eq!(settings.packed_bools, 0b1 | 0b10 << 1 | 0b1 << 3 | 300 << 4);
+
+    settings.set_level(LogLevel::Info);
+    assert_eq!(settings.get_level(), Ok(LogLevel::Info));
+    assert_eq!(settings.get_wide(), Ok(Wide::Large));
+}
+
+#[test]
+fn test_generated_mapping() {
+    assert_eq!(u8::from(LogLevel::Error), 1);
+    assert_eq!(LogLevel::try_from(3u8), Ok(LogLevel::Info));
+    assert_eq!(LogLevel::try_from(4u8), Err(4));
+    assert_eq!(u16::from(Wide::Large), 300);
+    assert_eq!(Wide::try_from(300u16), Ok(Wide::Large));
+    // The conversions exist for every unsigned type the discriminants fit in
+    assert_eq!(u64::from(LogLevel::Warn), 2);
+    assert_eq!(LogLevel::try_from(2u128), Ok(LogLevel::Warn));
+    assert_eq!(u32::from(Wide::Large), 300);
+}
+
+#[pack_bools]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u16)]
+enum Direction {
+    Up,
+    Down,
+}
+
+#[pack_bools]
+struct Motion {
+    #[pack_bools(enum, bits = 1)]
+    direction: Direction,
+}
+
+#[test]
+fn test_repr_narrower_than_field() {
+    let mut motion = Motion { packed_bools: 0 };
+    assert_eq!(motion.get_direction(), Ok(Direction::Up));
+    motion.set_direction(Direction::Down);
+    assert_eq!(motion.get_direction(), Ok(Direction::Down));
+    assert_eq!(u8::from(Direction::Down), 1);
+}
@@ -0,0 +1,84 @@
+use pack_bools::pack_bools;
+
+#[pack_bools]
+enum State {
+    Idle,
+    Connecting {
+        verbose: bool,
+        _retries: u32,
+        secure: bool,
+    },
+    Connected {
+        secure: bool,
+        #[pack_bools(get = get_compressed_stream)]
+        compressed: bool,
+        #[pack_bools(skip)]
+        _closing: bool,
+    },
+    Failed(String, bool),
+}
+
+#[test]
+fn test_enum_getters() {
+    let mut state = State::Connecting {
+        _retries: 3,
+        packed_bools: 0,
+    };
+    assert_eq!(state.get_verbose(), Some(false));
+    assert_eq!(state.get_secure(), Some(false));
+    assert_eq!(state.get_compressed_stream(), None);
+    assert!(state.set_verbose(true));
+    assert!(state.set_secure(true));
+    assert!(!state.set_compressed(true));
+    assert_eq!(state.get_verbose(), Some(true));
+    assert_eq!(state.get_secure(), Some(true));
+
+    state = State::Connected {
+        _closing: false,
+        packed_bools: 0,
+    };
+    assert_eq!(state.get_verbose(), None);
+    assert!(state.set_compressed(true));
+    assert_eq!(state.get_compressed_stream(), Some(true));
+    assert_eq!(state.get_secure(), Some(false));
+
+    state = State::Idle;
+    assert_eq!(state.get_secure(), None);
+    assert!(!state.set_secure(true));
+}
+
+#[test]
+fn test_enum_tuple_variant() {
+    let mut state = State::Failed(String::new(), 0);
+    assert_eq!(state.get_1(), Some(false));
+    assert!(state.set_1(true));
+    assert_eq!(state.get_1(), Some(true));
+    if let State::Failed(message, packed) = state {
+        assert!(message.is_empty());
+        assert_eq!(packed, 1);
+    }
+}
+
+#[pack_bools(newtype, type = u16, field = flags)]
+#[derive(Clone, Copy)]
+pub enum Shared {
+    A { x: bool, y: bool },
+    B { y: bool, z: bool },
+}
+
+#[test]
+fn test_enum_newtype() {
+    let mut shared = Shared::B {
+        flags: Default::default(),
+    };
+    shared.set_z(true);
+    assert_eq!(shared.get_x(), None);
+    assert_eq!(shared.get_y(), Some(false));
+    assert_eq!(shared.get_z(), Some(true));
+    let Shared::B { flags } = shared else {
+        unreachable!()
+    };
+    let shared = Shared::A { flags };
+    assert_eq!(shared.get_x(), Some(false));
+    assert_eq!(shared.get_y(), Some(true));
+}
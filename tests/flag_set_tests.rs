@@ -0,0 +1,87 @@
+use pack_bools::pack_bools;
+
+#[pack_bools(newtype = Permissions)]
+struct File {
+    read: bool,
+    write: bool,
+    #[pack_bools(bits = 2)]
+    owner: u8,
+    execute: bool,
+}
+
+#[test]
+fn test_flag_set_constants() {
+    assert_eq!(Permissions::READ.0, 0b00001);
+    assert_eq!(Permissions::WRITE.0, 0b00010);
+    assert_eq!(Permissions::EXECUTE.0, 0b10000);
+    assert_eq!(Permissions::all().0, 0b10011);
+    assert!(Permissions::empty().is_empty());
+    assert!(Permissions::all().is_all());
+}
+
+#[test]
+fn test_flag_set_operators() {
+    let read_write = Permissions::READ | Permissions::WRITE;
+    assert_eq!(read_write.0, 0b11);
+    assert_eq!((read_write & Permissions::WRITE).0, 0b10);
+    assert_eq!((read_write ^ Permissions::all()).0, 0b10000);
+    assert_eq!((read_write - Permissions::READ).0, 0b10);
+    assert_eq!((!read_write).0, 0b10000);
+
+    let mut permissions = Permissions::empty();
+    permissions |= Permissions::EXECUTE;
+    permissions ^= Permissions::READ;
+    assert_eq!(permissions.0, 0b10001);
+    permissions &= Permissions::READ;
+    permissions -= Permissions::READ;
+    assert!(permissions.is_empty());
+}
+
+#[test]
+fn test_flag_set_methods() {
+    let mut file = File {
+        packed_bools: Permissions::READ | Permissions::EXECUTE,
+    };
+    file.set_owner(3);
+    let permissions = &mut file.packed_bools;
+    assert!(permissions.contains(Permissions::READ | Permissions::EXECUTE));
+    assert!(!permissions.contains(Permissions::READ | Permissions::WRITE));
+    assert!(permissions.intersects(Permissions::READ | Permissions::WRITE));
+    assert!(!permissions.is_empty());
+
+    permissions.insert(Permissions::WRITE);
+    assert!(permissions.is_all());
+    permissions.remove(Permissions::READ);
+    permissions.toggle(Permissions::EXECUTE | Permissions::READ);
+    let set: Vec<_> = permissions.iter().map(|flag| flag.0).collect();
+    assert_eq!(set, [Permissions::READ.0, Permissions::WRITE.0]);
+    // Other packed fields are left unchanged by the complement
+    *permissions = !*permissions;
+    assert!(file.get_execute());
+    assert!(!file.get_read());
+    assert_eq!(file.get_owner(), 3);
+}
+
+#[pack_bools(newtype, type = [u8; 2])]
+struct Wide {
+    a: [bool; 7],
+    b: bool,
+    c: bool,
+    d: bool,
+}
+
+#[test]
+fn test_flag_set_words() {
+    assert_eq!(WidePackedBools::B.0, [0b1000_0000, 0]);
+    assert_eq!(WidePackedBools::D.0, [0, 0b10]);
+    let set = WidePackedBools::B | WidePackedBools::D;
+    assert_eq!((!set).0, [0, 0b1]);
+    assert!(set.contains(WidePackedBools::D));
+    assert!(!set.is_all());
+    assert_eq!(set.iter().count(), 2);
+    let wide = Wide {
+        packed_bools: set - WidePackedBools::B,
+    };
+    assert!(wide.get_d());
+    assert!(!wide.get_b());
+}
@@ -0,0 +1,65 @@
+use std::sync::atomic::AtomicU8;
+
+use pack_bools::pack_bools;
+
+#[pack_bools(flags)]
+struct Config {
+    verbose: bool,
+    use_colors: bool,
+    level: Option<bool>,
+    legacy_mode: bool,
+}
+
+#[test]
+fn test_flag_accessors() {
+    let mut config = Config { packed_bools: 0 };
+    config.set(ConfigFlag::UseColors, true);
+    config.set_level(Some(true));
+    assert!(config.get(ConfigFlag::UseColors));
+    assert!(config.get_use_colors());
+    assert!(!config.get(ConfigFlag::Verbose));
+
+    config.toggle(ConfigFlag::LegacyMode);
+    config.toggle(ConfigFlag::UseColors);
+    assert!(config.get_legacy_mode());
+    assert!(!config.get_use_colors());
+    // The tri-state isn't a flag, and is left untouched
+    assert_eq!(config.get_level(), Some(true));
+
+    assert!(config[ConfigFlag::LegacyMode]);
+    assert!(!config[ConfigFlag::Verbose]);
+}
+
+#[test]
+fn test_flag_all() {
+    assert_eq!(
+        ConfigFlag::ALL,
+        [
+            ConfigFlag::Verbose,
+            ConfigFlag::UseColors,
+            ConfigFlag::LegacyMode
+        ]
+    );
+    let mut config = Config { packed_bools: 0 };
+    for flag in ConfigFlag::ALL {
+        config.set(flag, true);
+    }
+    assert_eq!(config.packed_bools, 0b10011);
+}
+
+#[pack_bools(flags = Toggle, storage = atomic, type = [u8; 2])]
+struct Shared(u32, bool, #[pack_bools(name = late)] bool, [bool; 10], bool);
+
+#[test]
+fn test_flag_shared() {
+    let shared = Shared(5, Default::default());
+    shared.set(Toggle::Field1, true);
+    shared.toggle(Toggle::Late);
+    shared.toggle(Toggle::Field4);
+    assert!(shared.get_1());
+    assert!(shared.get_late());
+    assert!(shared[Toggle::Field4]);
+    let _: &[AtomicU8; 2] = &shared.1;
+    assert_eq!(Toggle::ALL.len(), 3);
+    assert_eq!(shared.0, 5);
+}
@@ -0,0 +1,249 @@
+//! Every generation mode has to compile in `#![no_std]` crates, so the generated code may only use
+//! `::core` paths
+#![no_std]
+
+use core::cell::Cell;
+use core::fmt::{self, Write};
+
+use pack_bools::pack_bools;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[repr(u8)]
+enum Speed {
+    Slow = 0,
+    Fast = 1,
+}
+
+impl From<Speed> for u8 {
+    fn from(value: Speed) -> Self {
+        value as u8
+    }
+}
+
+impl TryFrom<u8> for Speed {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Speed::Slow),
+            1 => Ok(Speed::Fast),
+            value => Err(value),
+        }
+    }
+}
+
+#[pack_bools]
+struct Inline {
+    a: bool,
+    b: Option<bool>,
+    c: [bool; 3],
+    #[pack_bools(bits = 3)]
+    d: u8,
+    #[pack_bools(enum, bits = 1)]
+    e: Speed,
+}
+
+#[pack_bools(newtype)]
+struct NewType {
+    #[pack_bools(default = true)]
+    a: bool,
+    #[pack_bools(default = false)]
+    b: Option<bool>,
+}
+
+#[pack_bools(type = [u8; 2])]
+struct Words {
+    a: [bool; 12],
+}
+
+#[pack_bools(storage = atomic, newtype)]
+struct Atomic {
+    #[pack_bools(default = true)]
+    a: bool,
+    b: Option<bool>,
+}
+
+#[pack_bools(storage = atomic)]
+struct AtomicWords {
+    a: [bool; 100],
+}
+
+#[pack_bools(storage = cell, newtype)]
+struct CellStorage {
+    a: bool,
+    b: [bool; 2],
+}
+
+#[pack_bools(storage = volatile)]
+struct Volatile {
+    a: bool,
+    #[pack_bools(bits = 2)]
+    b: u8,
+}
+
+#[pack_bools(newtype, flags, debug, new, with, raw_bits, unpacked)]
+#[derive(Debug, Clone, PartialEq)]
+struct Everything {
+    count: u32,
+    verbose: bool,
+    use_colors: bool,
+    level: Option<bool>,
+}
+
+#[pack_bools(flags, debug = struct, storage = atomic, with, derive_unpacked(Default))]
+struct SharedFlags {
+    a: bool,
+    b: [bool; 2],
+}
+
+#[pack_bools(newtype, storage = volatile, new)]
+#[repr(transparent)]
+struct Register {
+    a: bool,
+    b: bool,
+}
+
+#[pack_bools]
+enum Enum {
+    A { a: bool, b: Option<bool> },
+    B(bool),
+}
+
+#[test]
+fn test_no_std_plain() {
+    let mut inline = Inline { packed_bools: 0 };
+    inline.set_a(true);
+    inline.set_b(Some(false));
+    inline.set_c(2, true);
+    inline.set_d(5);
+    inline.set_e(Speed::Fast);
+    assert!(inline.get_a());
+    assert_eq!(inline.get_b(), Some(false));
+    assert_eq!(inline.get_c_array(), [false, false, true]);
+    assert_eq!(inline.get_d(), 5);
+    assert_eq!(inline.get_e(), Ok(Speed::Fast));
+
+    let newtype = NewType {
+        packed_bools: Default::default(),
+    };
+    assert!(newtype.get_a());
+    assert_eq!(newtype.get_b(), Some(false));
+
+    let mut words = Words {
+        packed_bools: [0; 2],
+    };
+    words.set_a(11, true);
+    assert_eq!(words.packed_bools, [0, 0b1000]);
+
+    let mut variant = Enum::A { packed_bools: 0 };
+    assert!(variant.set_b(Some(true)));
+    assert_eq!(variant.get_b(), Some(Some(true)));
+    assert_eq!(variant.get_0(), None);
+}
+
+#[test]
+fn test_no_std_shared() {
+    let atomic = Atomic {
+        packed_bools: Default::default(),
+    };
+    assert!(atomic.fetch_set_a());
+    assert_eq!(atomic.swap_b(Some(true)), None);
+    atomic.update_packed_bools(|snapshot| snapshot.set_a(false));
+    assert!(!atomic.load_packed_bools().get_a());
+
+    let atomic_words = AtomicWords {
+        packed_bools: Default::default(),
+    };
+    atomic_words.set_a(99, true);
+    assert!(atomic_words.get_a(99));
+
+    let cell = CellStorage {
+        packed_bools: Default::default(),
+    };
+    cell.set_b(1, true);
+    assert_eq!(cell.get_b_array(), [false, true]);
+    assert_eq!(cell.packed_bools.0.get(), 0b100);
+    let _: &Cell<u8> = &cell.packed_bools.0;
+
+    let mut volatile = Volatile { packed_bools: 0 };
+    volatile.set_b(3);
+    volatile.modify_packed_bools(|r| r.set_a(true));
+    assert_eq!(volatile.read_packed_bools().get_b(), 3);
+    assert_eq!(volatile.packed_bools, 0b111);
+}
+
+/// Formats into a fixed buffer, as there is no `format!` without `alloc`
+struct Buffer {
+    bytes: [u8; 128],
+    len: usize,
+}
+
+impl Buffer {
+    fn format(args: fmt::Arguments) -> Self {
+        let mut buffer = Buffer {
+            bytes: [0; 128],
+            len: 0,
+        };
+        buffer.write_fmt(args).unwrap();
+        buffer
+    }
+
+    fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.bytes[..self.len]).unwrap()
+    }
+}
+
+impl Write for Buffer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        self.bytes
+            .get_mut(self.len..end)
+            .ok_or(fmt::Error)?
+            .copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+#[test]
+fn test_no_std_generated_items() {
+    let mut everything = Everything::new(3, true, false, None).with_use_colors(true);
+    everything.set(EverythingFlag::Verbose, false);
+    everything.toggle(EverythingFlag::Verbose);
+    assert!(everything[EverythingFlag::UseColors]);
+    assert_eq!(EverythingFlag::ALL.len(), 2);
+    assert_eq!(everything.packed_bits(), 0b11);
+    everything.set_packed_bits(0b01);
+    assert!(!everything.get_use_colors());
+
+    let flags = EverythingPackedBools::VERBOSE | EverythingPackedBools::USE_COLORS;
+    assert!(flags.contains(EverythingPackedBools::USE_COLORS));
+    assert!((flags - EverythingPackedBools::VERBOSE).intersects(!EverythingPackedBools::VERBOSE));
+    assert_eq!(flags.iter().count(), 2);
+    assert_eq!(EverythingPackedBools::from_bits(flags.bits()), Some(flags));
+
+    assert_eq!(
+        Buffer::format(format_args!("{:?}", everything.packed_bools)).as_str(),
+        "EverythingPackedBools { verbose: true, use_colors: false, level: None }"
+    );
+    let unpacked = EverythingUnpacked::from(everything.clone());
+    assert_eq!(unpacked.count, 3);
+    assert!(unpacked.verbose);
+    assert_eq!(Everything::from(unpacked), everything);
+
+    let shared = SharedFlags::from(SharedFlagsUnpacked::default()).with_b([false, true]);
+    shared.toggle(SharedFlagsFlag::A);
+    shared.update_packed_bools(|snapshot| {
+        snapshot.set_b(0, true);
+    });
+    assert!(shared.load_packed_bools().get_a());
+    assert_eq!(
+        Buffer::format(format_args!("{shared:?}")).as_str(),
+        "SharedFlags { a: true, b: [true, true] }"
+    );
+
+    let mut register = Register::new(false, true);
+    register.modify_packed_bools(|r| r.set_a(true));
+    assert!(register.read_packed_bools().get_a());
+    assert!(register.get_b());
+}
@@ -0,0 +1,100 @@
+use pack_bools::pack_bools;
+
+#[pack_bools(order = msb0, type = u8)]
+struct Register {
+    enable: bool,
+    ready: bool,
+    #[pack_bools(bits = 3)]
+    mode: u8,
+    #[pack_bools(bit = 7)]
+    last: bool,
+}
+
+#[test]
+fn test_msb0() {
+    let mut register = Register { packed_bools: 0 };
+    register.set_enable(true);
+    assert_eq!(register.packed_bools, 0b1000_0000);
+    register.set_ready(true);
+    assert_eq!(register.packed_bools, 0b1100_0000);
+    register.set_mode(0b101);
+    assert_eq!(register.packed_bools, 0b1110_1000);
+    register.set_last(true);
+    assert_eq!(register.packed_bools, 0b1110_1001);
+    assert_eq!(register.get_mode(), 0b101);
+    assert_eq!(Register::ENABLE_BIT, 0);
+    assert_eq!(Register::MODE_BIT, 2);
+    assert_eq!(Register::LAST_BIT, 7);
+}
+
+#[pack_bools(order = msb0, type = [u8; 2], newtype)]
+#[derive(Default)]
+struct Frame {
+    #[pack_bools(default = true)]
+    start: Option<bool>,
+    flags: [bool; 10],
+}
+
+#[test]
+fn test_msb0_words() {
+    let mut frame = Frame::default();
+    assert_eq!(frame.packed_bools.0, [0b1100_0000, 0]);
+    frame.set_flags(5, true);
+    frame.set_flags(6, true);
+    assert_eq!(frame.packed_bools.0, [0b1100_0001, 0b1000_0000]);
+    assert_eq!(frame.get_start(), Some(true));
+    assert!(frame.get_flags(6));
+    // Like other multi-bit fields, the two bits of an `Option<bool>` keep their significance
+    frame.set_start(Some(false));
+    assert_eq!(frame.packed_bools.0, [0b0100_0001, 0b1000_0000]);
+}
+
+#[pack_bools(order = alphabetical)]
+struct Sorted {
+    zebra: bool,
+    apple: bool,
+    #[pack_bools(bit = 0)]
+    pinned: bool,
+    mango: bool,
+}
+
+#[test]
+fn test_alphabetical() {
+    assert_eq!(Sorted::PINNED_BIT, 0);
+    assert_eq!(Sorted::APPLE_BIT, 1);
+    assert_eq!(Sorted::MANGO_BIT, 2);
+    assert_eq!(Sorted::ZEBRA_BIT, 3);
+    let mut sorted = Sorted { packed_bools: 0 };
+    sorted.set_zebra(true);
+    assert_eq!(sorted.packed_bools, 1 << 3);
+    assert!(!sorted.get_apple());
+    assert!(!sorted.get_mango());
+    assert!(!sorted.get_pinned());
+}
+
+#[pack_bools(order = msb0, order = alphabetical, type = u8)]
+struct Combined {
+    b: bool,
+    a: bool,
+}
+
+#[test]
+fn test_combined_order() {
+    let mut combined = Combined { packed_bools: 0 };
+    combined.set_a(true);
+    assert_eq!(combined.packed_bools, 0b1000_0000);
+    assert!(!combined.get_b());
+}
+
+#[pack_bools]
+struct Tuple(bool, u8, bool);
+
+#[test]
+fn test_tuple_constants() {
+    assert_eq!(Tuple::FIELD_0_BIT, 0);
+    assert_eq!(Tuple::FIELD_2_BIT, 1);
+    let tuple = Tuple(1, 0);
+    assert!(!tuple.get_0());
+    assert!(!tuple.get_2());
+    assert_eq!(tuple.0, 1);
+}
@@ -0,0 +1,70 @@
+use pack_bools::pack_bools;
+
+#[pack_bools]
+struct Persisted {
+    a: bool,
+    #[pack_bools(bit = 5)]
+    b: bool,
+    #[pack_bools(bits = 3, bit = 1)]
+    c: u8,
+    d: Option<bool>,
+    e: bool,
+}
+
+#[test]
+fn test_pinned_layout() {
+    let mut persisted = Persisted { packed_bools: 0 };
+    persisted.set_b(true);
+    assert_eq!(persisted.packed_bools, 1 << 5);
+    persisted.set_c(0b101);
+    assert_eq!(persisted.packed_bools, 1 << 5 | 0b101 << 1);
+    // `d` doesn't fit in bit 4, so it skips over `b` to bits 6 and 7
+    persisted.set_d(Some(true));
+    assert_eq!(persisted.packed_bools, 0b11 << 6 | 1 << 5 | 0b101 << 1);
+    persisted.set_a(true);
+    persisted.set_e(true);
+    assert_eq!(
+        persisted.packed_bools,
+        1 << 8 | 0b11 << 6 | 1 << 5 | 0b101 << 1 | 1
+    );
+    assert!(persisted.get_a());
+    assert!(persisted.get_b());
+    assert_eq!(persisted.get_c(), 0b101);
+    assert_eq!(persisted.get_d(), Some(true));
+    assert!(persisted.get_e());
+}
+
+#[pack_bools(strict_bits)]
+struct Strict {
+    #[pack_bools(bit = 1)]
+    first: bool,
+    #[pack_bools(bit = 0)]
+    second: bool,
+    #[pack_bools(skip)]
+    _unpacked: bool,
+}
+
+#[test]
+fn test_strict_bits() {
+    let mut strict = Strict {
+        packed_bools: 0,
+        _unpacked: false,
+    };
+    strict.set_first(true);
+    assert_eq!(strict.packed_bools, 0b10);
+    assert!(!strict.get_second());
+}
+
+#[pack_bools]
+struct Sparse {
+    #[pack_bools(bit = 20)]
+    flag: bool,
+}
+
+#[test]
+fn test_pinned_sizing() {
+    let mut sparse = Sparse { packed_bools: 0 };
+    let _: u32 = sparse.packed_bools;
+    sparse.set_flag(true);
+    assert_eq!(sparse.packed_bools, 1 << 20);
+}
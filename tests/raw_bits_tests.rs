@@ -0,0 +1,150 @@
+use std::sync::atomic::AtomicU64;
+
+use pack_bools::pack_bools;
+
+#[pack_bools(raw_bits, reserve = 2)]
+struct Header {
+    a: bool,
+    #[pack_bools(reserved)]
+    _b: bool,
+    c: Option<bool>,
+}
+
+#[test]
+fn test_packed_bits() {
+    assert_eq!(Header::ALL_BITS, 0b0000_1101);
+    let mut header = Header { packed_bools: 0 };
+    header.set_a(true);
+    header.set_c(Some(true));
+    assert_eq!(header.packed_bits(), 0b1101);
+
+    // Bits that aren't assigned to any field are written as they are
+    header.set_packed_bits(0b11_0010);
+    assert_eq!(header.packed_bools, 0b11_0010);
+    assert!(!header.get_a());
+    assert_eq!(header.get_c(), None);
+}
+
+#[pack_bools(newtype = Flags, type = u16, raw_bits)]
+struct Settings {
+    a: bool,
+    #[pack_bools(bits = 3)]
+    b: u8,
+    #[pack_bools(bit = 8)]
+    c: bool,
+}
+
+#[test]
+fn test_from_bits() {
+    assert_eq!(Flags::ALL_BITS, 0b1_0000_1111);
+    assert_eq!(Settings::ALL_BITS, Flags::ALL_BITS);
+    assert_eq!(
+        Flags::from_bits(0b1_0000_0101).map(|flags| flags.bits()),
+        Some(0b1_0000_0101)
+    );
+    assert!(Flags::from_bits(0b1_0001_0101).is_none());
+    assert_eq!(
+        Flags::from_bits_truncate(0b1_0001_0101).bits(),
+        0b1_0000_0101
+    );
+    assert_eq!(Flags::from_bits_retain(0b1_0001_0101).bits(), 0b1_0001_0101);
+
+    let mut settings = Settings {
+        packed_bools: Flags::from_bits_truncate(0xffff),
+    };
+    assert!(settings.get_a());
+    assert_eq!(settings.get_b(), 0b111);
+    assert!(settings.get_c());
+    settings.set_packed_bits(0b1000);
+    assert_eq!(settings.get_b(), 0b100);
+    assert_eq!(settings.packed_bits(), settings.packed_bools.bits());
+}
+
+#[pack_bools(newtype, type = [u8; 2], raw_bits)]
+struct Wide {
+    a: [bool; 10],
+}
+
+#[test]
+fn test_from_bits_words() {
+    assert_eq!(WidePackedBools::ALL_BITS, [0xff, 0b11]);
+    assert!(WidePackedBools::from_bits([0xff, 0b100]).is_none());
+    assert_eq!(
+        WidePackedBools::from_bits_truncate([0xff, 0b111]).bits(),
+        [0xff, 0b11]
+    );
+    let wide = Wide {
+        packed_bools: WidePackedBools::from_bits([0, 0b10]).unwrap(),
+    };
+    assert!(wide.get_a(9));
+    assert_eq!(wide.packed_bits(), [0, 0b10]);
+}
+
+#[pack_bools(storage = atomic, raw_bits)]
+struct Shared {
+    a: [bool; 100],
+}
+
+#[test]
+fn test_packed_bits_atomic() {
+    let shared = Shared {
+        packed_bools: [AtomicU64::new(0), AtomicU64::new(0)],
+    };
+    shared.set_packed_bits([1, 1 << 35]);
+    assert!(shared.get_a(0));
+    assert!(shared.get_a(99));
+    assert_eq!(shared.packed_bits(), [1, 1 << 35]);
+    assert_eq!(Shared::ALL_BITS, [u64::MAX, (1 << 36) - 1]);
+}
+
+#[pack_bools(storage = atomic, ordering = Acquire, raw_bits)]
+struct Acquired {
+    a: bool,
+    b: bool,
+}
+
+#[test]
+fn test_set_packed_bits_acquire() {
+    let acquired = Acquired {
+        packed_bools: Default::default(),
+    };
+    acquired.set_packed_bits(0b10);
+    assert!(!acquired.get_a());
+    assert!(acquired.get_b());
+    assert_eq!(acquired.packed_bits(), 0b10);
+}
+
+#[pack_bools(newtype = Reserving, reserve = 2, raw_bits)]
+struct Reserved {
+    a: bool,
+    #[pack_bools(reserved)]
+    _b: bool,
+}
+
+#[test]
+fn test_from_bits_reserved() {
+    assert_eq!(Reserving::ALL_BITS, 0b1);
+    assert_eq!(Reserving::KNOWN_BITS, 0b1111);
+    assert_eq!(Reserved::KNOWN_BITS, Reserving::KNOWN_BITS);
+    // Bits reserved for later use are kept by every conversion
+    assert_eq!(
+        Reserving::from_bits(0b110).map(|flags| flags.bits()),
+        Some(0b110)
+    );
+    assert!(Reserving::from_bits(0b1_0000).is_none());
+    assert_eq!(Reserving::from_bits_truncate(0xff).bits(), 0b1111);
+}
+
+#[pack_bools(raw_bits = raw)]
+struct Renamed {
+    packed_bits: bool,
+}
+
+#[test]
+fn test_raw_bits_name() {
+    let mut renamed = Renamed { packed_bools: 0 };
+    renamed.set_packed_bits(true);
+    assert_eq!(renamed.raw(), 1);
+    renamed.set_raw(0);
+    assert!(!renamed.get_packed_bits());
+}
@@ -0,0 +1,39 @@
+use pack_bools::pack_bools;
+
+#[pack_bools(reserve = 6)]
+struct Header {
+    a: bool,
+    b: bool,
+    c: bool,
+}
+
+#[test]
+fn test_reserve_sizing() {
+    let header = Header { packed_bools: 0 };
+    let _: u16 = header.packed_bools;
+    assert!(!header.get_a());
+}
+
+#[pack_bools]
+struct Placeholders {
+    a: bool,
+    #[pack_bools(reserved)]
+    _future: [bool; 3],
+    b: bool,
+    #[pack_bools(reserved, bit = 7)]
+    _last: bool,
+}
+
+#[test]
+fn test_reserved_fields() {
+    let mut placeholders = Placeholders {
+        packed_bools: 0b1000_1110,
+    };
+    placeholders.set_a(true);
+    placeholders.set_b(true);
+    assert_eq!(placeholders.packed_bools, 0b1001_1111);
+    placeholders.set_a(false);
+    placeholders.set_b(false);
+    // The reserved bits are left untouched
+    assert_eq!(placeholders.packed_bools, 0b1000_1110);
+}
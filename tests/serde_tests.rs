@@ -0,0 +1,186 @@
+#![cfg(feature = "serde")]
+
+use pack_bools::pack_bools;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+enum Mode {
+    Fast,
+    Slow,
+}
+
+impl From<Mode> for u8 {
+    fn from(value: Mode) -> Self {
+        value as u8
+    }
+}
+
+impl TryFrom<u8> for Mode {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Mode::Fast),
+            1 => Ok(Mode::Slow),
+            _ => Err(()),
+        }
+    }
+}
+
+#[pack_bools(newtype, serde)]
+#[derive(Debug, Serialize, Deserialize)]
+struct Config {
+    name: String,
+    verbose: bool,
+    #[pack_bools(default = true)]
+    colors: bool,
+    level: Option<bool>,
+    #[pack_bools(bits = 3)]
+    depth: u8,
+    #[pack_bools(enum, bits = 1)]
+    mode: Mode,
+    channels: [bool; 2],
+}
+
+#[test]
+fn test_newtype_fields() {
+    let mut config = Config {
+        name: "test".to_string(),
+        packed_bools: Default::default(),
+    };
+    config.set_level(Some(false));
+    config.set_depth(5);
+    config.set_mode(Mode::Slow);
+    config.set_channels(1, true);
+    let json = serde_json::to_string(&config).unwrap();
+    assert_eq!(
+        json,
+        r#"{"name":"test","verbose":false,"colors":true,"level":false,"depth":5,"mode":"Slow","channels":[false,true]}"#
+    );
+
+    let config: Config = serde_json::from_str(&json).unwrap();
+    assert_eq!(config.name, "test");
+    assert!(!config.get_verbose());
+    assert!(config.get_colors());
+    assert_eq!(config.get_level(), Some(false));
+    assert_eq!(config.get_depth(), 5);
+    assert_eq!(config.get_mode(), Ok(Mode::Slow));
+    assert!(!config.get_channels(0));
+    assert!(config.get_channels(1));
+}
+
+#[test]
+fn test_newtype_missing_fields() {
+    // Missing packed fields keep their default value, and unknown fields are ignored
+    let config: Config =
+        serde_json::from_str(r#"{"name":"test","verbose":true,"unknown":[1,2]}"#).unwrap();
+    assert!(config.get_verbose());
+    assert!(config.get_colors());
+    assert_eq!(config.get_level(), None);
+    assert_eq!(config.get_depth(), 0);
+    assert_eq!(config.get_mode(), Ok(Mode::Fast));
+
+    let error = serde_json::from_str::<Config>(r#"{"name":"test","depth":8}"#).unwrap_err();
+    assert!(error
+        .to_string()
+        .starts_with("8 doesn't fit in the bits of `depth`"));
+}
+
+#[test]
+fn test_newtype_alone() {
+    let mut packed = ConfigPackedBools::default();
+    packed.0 |= 1;
+    let json = serde_json::to_string(&packed).unwrap();
+    assert_eq!(
+        json,
+        r#"{"verbose":true,"colors":true,"level":null,"depth":0,"mode":"Fast","channels":[false,false]}"#
+    );
+    let packed: ConfigPackedBools = serde_json::from_str(&json).unwrap();
+    assert_eq!(packed.0 & 0b11, 0b11);
+}
+
+#[pack_bools(serde, storage = atomic)]
+#[derive(Serialize, Deserialize)]
+struct Shared<T> {
+    value: T,
+    ready: bool,
+    #[pack_bools(reserved)]
+    _unused: bool,
+    done: bool,
+}
+
+#[test]
+fn test_inline_fields() {
+    let shared = Shared {
+        value: 3,
+        packed_bools: Default::default(),
+    };
+    shared.set_done(true);
+    let json = serde_json::to_string(&shared).unwrap();
+    assert_eq!(json, r#"{"value":3,"ready":false,"done":true}"#);
+
+    let shared: Shared<i32> = serde_json::from_str(r#"{"ready":true,"value":4}"#).unwrap();
+    assert_eq!(shared.value, 4);
+    assert!(shared.get_ready());
+    assert!(!shared.get_done());
+}
+
+#[pack_bools(newtype, serde = compact)]
+#[derive(Serialize, Deserialize)]
+struct Compact {
+    a: bool,
+    b: bool,
+}
+
+#[pack_bools(serde = compact)]
+#[derive(Serialize)]
+struct CompactInline {
+    a: bool,
+    b: bool,
+}
+
+#[test]
+fn test_compact() {
+    let mut compact = Compact {
+        packed_bools: Default::default(),
+    };
+    compact.set_b(true);
+    let json = serde_json::to_string(&compact).unwrap();
+    assert_eq!(json, r#"{"packed_bools":2}"#);
+    let compact: Compact = serde_json::from_str(&json).unwrap();
+    assert!(!compact.get_a());
+    assert!(compact.get_b());
+
+    let inline = CompactInline { packed_bools: 1 };
+    assert_eq!(
+        serde_json::to_string(&inline).unwrap(),
+        r#"{"packed_bools":1}"#
+    );
+}
+
+#[pack_bools(serde)]
+#[derive(Serialize, Deserialize)]
+struct Large {
+    first: bool,
+    lanes: [bool; 40],
+}
+
+#[test]
+fn test_large_array() {
+    let mut large = Large {
+        packed_bools: Default::default(),
+    };
+    large.set_lanes(39, true);
+    let json = serde_json::to_string(&large).unwrap();
+    let mut lanes = vec!["false"; 40];
+    lanes[39] = "true";
+    assert_eq!(
+        json,
+        format!(r#"{{"first":false,"lanes":[{}]}}"#, lanes.join(","))
+    );
+
+    let large: Large = serde_json::from_str(&json).unwrap();
+    assert!(large.get_lanes(39));
+    assert!(!large.get_lanes(0));
+    assert!(serde_json::from_str::<Large>(r#"{"lanes":[true]}"#).is_err());
+}
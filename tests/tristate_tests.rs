@@ -0,0 +1,59 @@
+use pack_bools::pack_bools;
+
+#[pack_bools(newtype)]
+#[derive(Default)]
+struct TriStates {
+    verbose: bool,
+    colors: Option<bool>,
+    #[pack_bools(default = true)]
+    dark_mode: std::option::Option<bool>,
+    #[pack_bools(default = false)]
+    compact: Option<bool>,
+    #[pack_bools(default = none)]
+    unset: Option<bool>,
+    #[pack_bools(skip)]
+    skipped: Option<bool>,
+}
+
+#[test]
+fn test_tristate_defaults() {
+    let tri = TriStates::default();
+    assert!(!tri.get_verbose());
+    assert_eq!(tri.get_colors(), None);
+    assert_eq!(tri.get_dark_mode(), Some(true));
+    assert_eq!(tri.get_compact(), Some(false));
+    assert_eq!(tri.get_unset(), None);
+    assert_eq!(tri.skipped, None);
+    assert_eq!(tri.packed_bools.0, 0b11 << 3 | 0b01 << 5);
+}
+
+#[test]
+fn test_tristate_set() {
+    let mut tri = TriStates::default();
+    for value in [Some(true), None, Some(false), Some(true)] {
+        tri.set_colors(value);
+        assert_eq!(tri.get_colors(), value);
+        assert_eq!(tri.get_dark_mode(), Some(true));
+        assert!(!tri.get_verbose());
+    }
+    tri.set_dark_mode(None);
+    assert_eq!(tri.get_dark_mode(), None);
+    assert_eq!(tri.get_colors(), Some(true));
+}
+
+#[pack_bools]
+enum Request {
+    Get { cached: Option<bool>, secure: bool },
+    Put,
+}
+
+#[test]
+fn test_tristate_enum() {
+    let mut request = Request::Get { packed_bools: 0 };
+    assert_eq!(request.get_cached(), Some(None));
+    assert!(request.set_cached(Some(false)));
+    assert_eq!(request.get_cached(), Some(Some(false)));
+    assert_eq!(request.get_secure(), Some(false));
+    request = Request::Put;
+    assert_eq!(request.get_cached(), None);
+}
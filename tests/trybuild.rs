@@ -10,4 +10,40 @@ fn trybuild() {
     cases.compile_fail("tests/compile/no_setters.rs");
     cases.compile_fail("tests/compile/no_getters_setters.rs");
     cases.compile_fail("tests/compile/private_getters.rs");
+    cases.compile_fail("tests/compile/tuple_unnamed_getter.rs");
+    cases.compile_fail("tests/compile/position_out_of_range.rs");
+    cases.compile_fail("tests/compile/enum_defaults.rs");
+    cases.compile_fail("tests/compile/enum_field_types.rs");
+    cases.compile_fail("tests/compile/invalid_item.rs");
+    cases.pass("tests/compile/shape_named.rs");
+    cases.pass("tests/compile/shape_tuple.rs");
+    cases.pass("tests/compile/shape_unit.rs");
+    cases.compile_fail("tests/compile/invalid_bits.rs");
+    cases.compile_fail("tests/compile/too_many_bits.rs");
+    cases.compile_fail("tests/compile/enum_discriminant_too_large.rs");
+    cases.compile_fail("tests/compile/options_on_fieldless_enum.rs");
+    cases.compile_fail("tests/compile/tristate_defaults.rs");
+    cases.compile_fail("tests/compile/too_many_words.rs");
+    cases.compile_fail("tests/compile/wider_than_word.rs");
+    cases.compile_fail("tests/compile/bit_overlap.rs");
+    cases.compile_fail("tests/compile/bit_out_of_range.rs");
+    cases.compile_fail("tests/compile/strict_bits.rs");
+    cases.compile_fail("tests/compile/bit_straddles_words.rs");
+    cases.compile_fail("tests/compile/atomic_u128.rs");
+    cases.compile_fail("tests/compile/ordering_without_atomic.rs");
+    cases.compile_fail("tests/compile/ordering_with_cell.rs");
+    cases.compile_fail("tests/compile/flags_on_enum.rs");
+    cases.compile_fail("tests/compile/debug_inline.rs");
+    cases.compile_fail("tests/compile/debug_on_enum.rs");
+    cases.compile_fail("tests/compile/unpacked_on_enum.rs");
+    cases.compile_fail("tests/compile/derive_unpacked_on_enum.rs");
+    cases.compile_fail("tests/compile/new_on_enum.rs");
+    cases.compile_fail("tests/compile/with_on_enum.rs");
+    cases.compile_fail("tests/compile/flag_named_all_bits.rs");
+    cases.compile_fail("tests/compile/raw_bits_clash.rs");
+    cases.compile_fail("tests/compile/flag_variant_keyword.rs");
+    cases.compile_fail("tests/compile/flag_variant_duplicate.rs");
+    if cfg!(feature = "serde") {
+        cases.compile_fail("tests/compile/serde_tuple.rs");
+    }
 }
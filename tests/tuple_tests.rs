@@ -0,0 +1,64 @@
+use pack_bools::pack_bools;
+
+#[pack_bools]
+#[derive(Default)]
+struct Flags(u32, bool, bool);
+
+#[test]
+fn test_positional_names() {
+    let mut flags = Flags::default();
+    assert!(!flags.get_1());
+    assert!(!flags.get_2());
+    flags.set_2(true);
+    assert!(!flags.get_1());
+    assert!(flags.get_2());
+    assert_eq!(flags.0, 0);
+    assert_eq!(flags.1, 0b10);
+}
+
+#[pack_bools(position = 0)]
+#[derive(Default)]
+struct Named(
+    #[pack_bools(name = verbose)] bool,
+    String,
+    #[pack_bools(name = colors, set = set_use_colors)] bool,
+);
+
+#[test]
+fn test_local_names_and_position() {
+    let mut named = Named::default();
+    named.set_verbose(true);
+    named.set_use_colors(true);
+    named.set_use_colors(false);
+    assert!(named.get_verbose());
+    assert!(!named.get_colors());
+    assert_eq!(named.0, 0b01);
+    assert_eq!(named.1, "");
+}
+
+#[pack_bools(newtype, position = 1)]
+#[derive(Default)]
+struct WithNewtype(u8, #[pack_bools(default = true)] bool, u16);
+
+#[test]
+fn test_tuple_newtype() {
+    let x = WithNewtype::default();
+    assert!(x.get_1());
+    assert_eq!(x.0, 0);
+    assert_eq!(x.2, 0);
+}
+
+#[pack_bools(position = 0)]
+#[derive(Default)]
+struct NamedPosition {
+    a: u8,
+    b: bool,
+}
+
+#[test]
+fn test_named_position() {
+    let x = NamedPosition::default();
+    assert!(!x.get_b());
+    assert_eq!(x.packed_bools, 0);
+    assert_eq!(x.a, 0);
+}
@@ -0,0 +1,176 @@
+use pack_bools::pack_bools;
+use serde::Deserialize as Load;
+
+#[pack_bools]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Level {
+    Low,
+    Medium,
+    High,
+}
+
+#[pack_bools(unpacked, raw_bits)]
+#[derive(Debug, Clone, PartialEq)]
+struct Config<'a> {
+    name: &'a str,
+    verbose: bool,
+    level: Option<bool>,
+    #[pack_bools(bits = 3)]
+    depth: u8,
+    #[pack_bools(enum, bits = 2)]
+    priority: Level,
+    channels: [bool; 3],
+    #[pack_bools(reserved)]
+    _unused: bool,
+    count: u32,
+}
+
+#[test]
+fn test_unpacked() {
+    let config = Config::from(ConfigUnpacked {
+        name: "test",
+        verbose: true,
+        level: Some(false),
+        depth: 5,
+        priority: Level::High,
+        channels: [false, true, true],
+        _unused: false,
+        count: 7,
+    });
+    assert_eq!(config.name, "test");
+    assert!(config.get_verbose());
+    assert_eq!(config.get_level(), Some(false));
+    assert_eq!(config.get_depth(), 5);
+    assert_eq!(config.get_priority(), Ok(Level::High));
+    assert_eq!(config.get_channels_array(), [false, true, true]);
+    assert_eq!(config.count, 7);
+
+    let ConfigUnpacked {
+        name,
+        verbose,
+        level,
+        depth,
+        priority,
+        channels,
+        count,
+        ..
+    } = config.clone().try_into().unwrap();
+    assert_eq!(name, "test");
+    assert!(verbose);
+    assert_eq!(level, Some(false));
+    assert_eq!(depth, 5);
+    assert_eq!(priority, Level::High);
+    assert_eq!(channels, [false, true, true]);
+    assert_eq!(count, 7);
+
+    let unpacked = ConfigUnpacked::try_from(config.clone()).unwrap();
+    assert_eq!(Config::from(unpacked), config);
+
+    // Bits that don't convert back to an enum give the struct back
+    let mut invalid = config.clone();
+    invalid.set_packed_bits(0b11 << 6);
+    assert_eq!(invalid.get_priority(), Err(3));
+    assert_eq!(ConfigUnpacked::try_from(invalid.clone()), Err(invalid));
+}
+
+#[pack_bools(newtype, unpacked = Flags, storage = atomic)]
+struct Shared<T>(T, bool, #[pack_bools(default = true)] bool)
+where
+    T: Copy;
+
+#[test]
+fn test_unpacked_tuple() {
+    let shared = Shared::from(Flags(3, true, false));
+    assert_eq!(shared.0, 3);
+    assert!(shared.get_1());
+    assert!(!shared.get_2());
+    shared.set_2(true);
+
+    let Flags(value, first, second) = shared.into();
+    assert_eq!(value, 3);
+    assert!(first);
+    assert!(second);
+}
+
+/// The arguments of the program, documenting the unpacked struct as well
+#[pack_bools(derive_unpacked(serde::Deserialize))]
+#[derive(Debug)]
+#[serde(rename_all = "kebab-case")]
+struct Args {
+    #[serde(default)]
+    output_name: String,
+    #[serde(rename = "v")]
+    verbose: bool,
+    #[serde(default)]
+    use_colors: bool,
+}
+
+#[test]
+fn test_derive_unpacked() {
+    let unpacked: ArgsUnpacked =
+        serde_json::from_str(r#"{"output-name":"out.txt","v":true}"#).unwrap();
+    let args = Args::from(unpacked);
+    assert_eq!(args.output_name, "out.txt");
+    assert!(args.get_verbose());
+    assert!(!args.get_use_colors());
+    assert_eq!(
+        format!("{args:?}"),
+        r#"Args { output_name: "out.txt", packed_bools: 1 }"#
+    );
+}
+
+/// Only the unpacked struct derives `Deserialize`, so only its helper attributes are removed from the
+/// struct, which keeps the attributes used by its own `Serialize`
+#[pack_bools(derive_unpacked(serde::Deserialize))]
+#[derive(serde::Serialize)]
+#[rustfmt::skip]
+struct Renamed {
+    #[serde(rename = "n")]
+    name: String,
+    verbose: bool,
+}
+
+#[test]
+fn test_derive_unpacked_keeps_attributes() {
+    let unpacked: RenamedUnpacked = serde_json::from_str(r#"{"n":"out","verbose":true}"#).unwrap();
+    let renamed = Renamed::from(unpacked);
+    assert!(renamed.get_verbose());
+    assert_eq!(
+        serde_json::to_string(&renamed).unwrap(),
+        r#"{"n":"out","packed_bools":1}"#
+    );
+}
+
+#[pack_bools(unpacked, storage = volatile)]
+#[repr(transparent)]
+struct Register {
+    enable: bool,
+    irq: bool,
+}
+
+#[test]
+fn test_unpacked_repr() {
+    let register = Register::from(RegisterUnpacked {
+        enable: true,
+        irq: false,
+    });
+    assert_eq!(std::mem::size_of::<Register>(), 1);
+    let RegisterUnpacked { enable, irq } = register.into();
+    assert!(enable);
+    assert!(!irq);
+}
+
+#[pack_bools(derive_unpacked(Load), unpacked_helpers(serde))]
+struct Aliased {
+    #[serde(default)]
+    name: String,
+    verbose: bool,
+}
+
+#[test]
+fn test_unpacked_helpers() {
+    let unpacked: AliasedUnpacked = serde_json::from_str(r#"{"verbose":true}"#).unwrap();
+    let aliased = Aliased::from(unpacked);
+    assert!(aliased.name.is_empty());
+    assert!(aliased.get_verbose());
+}
@@ -0,0 +1,64 @@
+use pack_bools::pack_bools;
+
+#[pack_bools(storage = volatile, type = u32)]
+#[repr(transparent)]
+struct Control {
+    enable: bool,
+    interrupt: bool,
+    #[pack_bools(bits = 4)]
+    prescaler: u8,
+}
+
+#[pack_bools(storage = volatile, type = u32, newtype = Status)]
+#[repr(transparent)]
+struct StatusRegister {
+    ready: bool,
+    #[pack_bools(bit = 31)]
+    error: bool,
+}
+
+#[repr(C)]
+struct Peripheral {
+    control: Control,
+    status: StatusRegister,
+}
+
+#[test]
+fn test_volatile_registers() {
+    // Stands in for the memory-mapped registers of a peripheral
+    let mut memory = [0u32, 1 << 31];
+    let peripheral = unsafe { &mut *(memory.as_mut_ptr() as *mut Peripheral) };
+
+    assert!(!peripheral.control.get_enable());
+    assert!(peripheral.status.get_error());
+    assert!(!peripheral.status.get_ready());
+
+    peripheral.control.set_enable(true);
+    peripheral.control.set_prescaler(0b1010);
+    peripheral.status.set_error(false);
+    assert!(peripheral.control.get_enable());
+    assert_eq!(peripheral.control.get_prescaler(), 0b1010);
+    assert_eq!(memory, [0b10_1001, 0]);
+}
+
+#[test]
+fn test_volatile_modify() {
+    let mut memory = [0b01u32];
+    let control = unsafe { &mut *(memory.as_mut_ptr() as *mut Control) };
+
+    let previous = control.modify_packed_bools(|r| {
+        r.set_enable(false);
+        r.set_interrupt(true);
+        r.set_prescaler(3);
+    });
+    assert!(previous.get_enable());
+    assert!(!previous.get_interrupt());
+
+    let current = control.read_packed_bools();
+    assert!(!current.get_enable());
+    assert!(current.get_interrupt());
+    assert_eq!(current.get_prescaler(), 3);
+
+    control.write_packed_bools(previous);
+    assert_eq!(memory, [0b01]);
+}
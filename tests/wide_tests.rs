@@ -0,0 +1,84 @@
+use pack_bools::pack_bools;
+
+#[pack_bools]
+struct FeatureMatrix {
+    first: bool,
+    features: [bool; 120],
+    #[pack_bools(bits = 8)]
+    level: u8,
+    last: bool,
+}
+
+#[test]
+fn test_wide_auto() {
+    let mut matrix = FeatureMatrix {
+        packed_bools: [0; 3],
+    };
+    let _: [u64; 3] = matrix.packed_bools;
+    matrix.set_first(true);
+    matrix.set_features(63, true);
+    matrix.set_features(64, true);
+    matrix.set_features(119, true);
+    matrix.set_level(0xAB);
+    matrix.set_last(true);
+    assert!(matrix.get_first());
+    assert!(matrix.get_features(63));
+    assert!(matrix.get_features(64));
+    assert!(!matrix.get_features(65));
+    assert!(matrix.get_features(119));
+    assert_eq!(matrix.get_level(), 0xAB);
+    assert!(matrix.get_last());
+    // `level` would straddle the second and third words, so it starts at the third word
+    assert_eq!(
+        matrix.packed_bools,
+        [1, 1 | 1 << 1 | 1 << 56, 0xAB | 1 << 8]
+    );
+}
+
+#[pack_bools(type = [u8; 4], newtype)]
+#[derive(Default)]
+struct Bytes {
+    a: bool,
+    #[pack_bools(default = true)]
+    b: bool,
+    #[pack_bools(bits = 7)]
+    c: u8,
+    #[pack_bools(default = false)]
+    d: Option<bool>,
+}
+
+#[test]
+fn test_wide_fixed() {
+    let mut bytes = Bytes::default();
+    assert_eq!(bytes.packed_bools.0, [0b10, 0, 0b01, 0]);
+    bytes.set_c(0x7F);
+    bytes.set_d(Some(true));
+    assert!(!bytes.get_a());
+    assert!(bytes.get_b());
+    assert_eq!(bytes.get_c(), 0x7F);
+    assert_eq!(bytes.get_d(), Some(true));
+    assert_eq!(bytes.packed_bools.0, [0b10, 0x7F, 0b11, 0]);
+}
+
+#[pack_bools(type = [u16; 2])]
+enum Layer {
+    Visible { flags: [bool; 20], locked: bool },
+    Hidden,
+}
+
+#[test]
+fn test_wide_enum() {
+    let mut layer = Layer::Visible {
+        packed_bools: [0; 2],
+    };
+    assert!(layer.set_flags(17, true));
+    assert!(layer.set_locked(true));
+    assert_eq!(layer.get_flags(17), Some(true));
+    assert_eq!(layer.get_locked(), Some(true));
+    let Layer::Visible { packed_bools } = layer else {
+        unreachable!()
+    };
+    assert_eq!(packed_bools, [0, 1 << 1 | 1 << 4]);
+    layer = Layer::Hidden;
+    assert_eq!(layer.get_flags(17), None);
+}
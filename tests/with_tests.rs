@@ -0,0 +1,100 @@
+use std::sync::atomic::Ordering;
+
+use pack_bools::pack_bools;
+
+#[pack_bools]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Level {
+    Low,
+    High,
+}
+
+#[pack_bools(with)]
+#[derive(Debug, Clone)]
+struct Flags {
+    a: bool,
+    b: Option<bool>,
+    #[pack_bools(bits = 8)]
+    full: u8,
+    #[pack_bools(bits = 3)]
+    depth: u8,
+    #[pack_bools(enum, bits = 1)]
+    level: Level,
+    channels: [bool; 2],
+    #[pack_bools(no_with)]
+    plain: bool,
+}
+
+// Bools, tristates and integers filling their type can be set in constants
+const DEFAULT: Flags = Flags { packed_bools: 0 }
+    .with_a(true)
+    .with_b(Some(false))
+    .with_full(200);
+
+#[test]
+fn test_with() {
+    assert!(DEFAULT.get_a());
+    assert_eq!(DEFAULT.get_b(), Some(false));
+    assert_eq!(DEFAULT.get_full(), 200);
+
+    let flags = DEFAULT
+        .clone()
+        .with_depth(5)
+        .with_level(Level::High)
+        .with_channels([false, true]);
+    assert_eq!(flags.get_depth(), 5);
+    assert_eq!(flags.get_level(), Ok(Level::High));
+    assert_eq!(flags.get_channels_array(), [false, true]);
+    assert!(!flags.get_plain());
+}
+
+#[test]
+fn test_chained_setters() {
+    let mut flags = DEFAULT.clone();
+    flags
+        .set_a(false)
+        .set_plain(true)
+        .set_channels(0, true)
+        .set_channels_array([true, true])
+        .set_depth(2);
+    assert!(!flags.get_a());
+    assert!(flags.get_plain());
+    assert_eq!(flags.get_channels_array(), [true, true]);
+    assert_eq!(flags.get_depth(), 2);
+}
+
+#[pack_bools(newtype, storage = atomic, with = pub(crate) and_%)]
+struct Shared {
+    ready: bool,
+    #[pack_bools(with = finished)]
+    done: bool,
+}
+
+#[test]
+fn test_with_template() {
+    let shared = Shared {
+        packed_bools: Default::default(),
+    }
+    .and_ready(true)
+    .finished(true);
+    assert!(shared.get_ready());
+    assert!(shared.get_done());
+    shared.set_ready(false).set_done(false);
+    assert_eq!(shared.packed_bools.0.load(Ordering::SeqCst), 0);
+}
+
+#[pack_bools]
+struct Local {
+    #[pack_bools(with = with_a)]
+    a: bool,
+    b: bool,
+}
+
+#[test]
+fn test_local_with() {
+    let mut local = Local { packed_bools: 0 }.with_a(true);
+    // Setters only return `self` with the global option
+    let () = local.set_b(true);
+    assert!(local.get_a());
+    assert!(local.get_b());
+}